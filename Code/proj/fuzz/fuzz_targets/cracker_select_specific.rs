@@ -0,0 +1,18 @@
+// Cracks a column on every table type and runs a sequence of cracker selects against it. Input
+// is decoded with differential::decode_workload.
+//
+//     cargo fuzz run cracker_select_specific
+
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate lib;
+
+use lib::differential;
+
+fuzz_target!(|data: &[u8]| {
+    let (column, queries) = differential::decode_workload(data);
+    if let Err(e) = differential::check_select_specific(&column, &queries) {
+        panic!("{:?} with queries {:?}: {}", column, queries, e);
+    }
+});
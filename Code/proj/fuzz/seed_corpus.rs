@@ -0,0 +1,52 @@
+// Writes the seed corpus for the fuzz targets from trees, with the tree's src column cracked and
+// its vertices queried in the order a BFS would first reach them, followed by some values which
+// are absent from the column. The trees are built here by a fixed rule rather than by datagen, so
+// that changes to datagen's generators don't change the checked-in corpus.
+//
+//     cargo run --bin seed_corpus
+
+extern crate lib;
+
+use lib::differential;
+
+use std::fs;
+
+fn main() {
+    let dir = "corpus/cracker_select_specific";
+    fs::create_dir_all(dir).unwrap();
+    for &n in &[2, 3, 5, 8, 13, 30, 100, 300] {
+        let (src, dst) = tree(n);
+        let mut queries = bfs_order(&src, &dst, 1);
+        queries.extend_from_slice(&[0, n + 1, -1]);
+        let bytes = differential::encode_workload(&src, &queries);
+        fs::write(format!("{}/tree_{}", dir, n), bytes).unwrap();
+    }
+}
+
+// A bidirectionally connected tree on 1 to n, where each node k after the first is connected to
+// one of the nodes before it, picked by scrambling k.
+fn tree(n: i64) -> (Vec<i64>, Vec<i64>) {
+    let mut src = Vec::new();
+    let mut dst = Vec::new();
+    for k in 2..(n + 1) {
+        let parent = 1 + (k * 7919 + k * k) % (k - 1);
+        src.extend_from_slice(&[k, parent]);
+        dst.extend_from_slice(&[parent, k]);
+    }
+    (src, dst)
+}
+
+fn bfs_order(src: &[i64], dst: &[i64], start_node: i64) -> Vec<i64> {
+    let mut order = vec![start_node];
+    let mut i = 0;
+    while i < order.len() {
+        let v = order[i];
+        for j in 0..src.len() {
+            if src[j] == v && !order.contains(&dst[j]) {
+                order.push(dst[j]);
+            }
+        }
+        i += 1;
+    }
+    order
+}
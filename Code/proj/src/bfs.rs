@@ -1,9 +1,6 @@
 use time::PreciseTime;
 use bit_vec::BitVec;
 
-use rand::Rng; // HMM
-use rand;
-
 use datagen;
 use decomposed_cracking;
 use recognitive_compression;
@@ -12,39 +9,48 @@ use underswap_rle_compression;
 use overswap_rle_compression;
 use time::Duration;
 use quicksort::*;
+use graph;
+use graph::Graph;
+use traversal;
+use vertex_dictionary;
 
 /* BFS:
     Given an adjacency list of two i64 vectors, SRC_NODE and DST_NODE, this function visits every
     node in the graph from START_NODE.
 
     Returns the nodes visited in the order in which they were visited.
+
+    The kernels index their visited bit vectors by ID - 1, so they run over vertices numbered 1 to
+    n. The entry points renumber any graph so, through a VertexDictionary, and give the visited
+    nodes back in the graph's own IDs. Tables passed to the _adjl kernels must be numbered so too.
 */
 
-pub fn run() {
+pub fn run(seed: usize) {
     let n = 1000;
-    let (src, dst) = datagen::randomly_connected_tree(n);
-        let start_node = rand::thread_rng().gen_range(1, n);
+    let (src, dst) = datagen::randomly_connected_tree(n, seed);
+    let start_node = datagen::random_start_node(n, seed);
     let _visited = underswap_rle_bfs(src, dst, start_node);
 }
 
 // Benchmark for single BFS runs
 
-// Prints to stdout valid csv lines containing the results of bfs benchmarks..
-pub fn benchmark_sparse_bfs_csv(graph_sizes: Vec<i64>) {
-    println!("nodes,edges,density,unoptimised,preclustered,preclusteredRLE,decomposed,recognitive,compactive,underswapRLE,overswapRLE");
+// Prints to stdout valid csv lines containing the results of bfs benchmarks. Each line records the
+// seed its graph was generated from, so that it can be replayed.
+pub fn benchmark_sparse_bfs_csv(graph_sizes: Vec<i64>, seed: usize) {
+    println!("seed,nodes,edges,density,unoptimised,preclustered,preclusteredRLE,decomposed,recognitive,compactive,underswapRLE,overswapRLE");
     for n in graph_sizes {
-        benchmark_sparse_bfs(n);
+        benchmark_sparse_bfs(n, seed);
     }
 }
 
 // Given a number of nodes N, produces a sparse connected graph of that many nodes and gets runtime
 // performance for each of adaptive, unoptimised and preclustering methods. It prints to stdout a
 // line of a csv file.
-fn benchmark_sparse_bfs(n: i64) {
-    let (src, dst) = datagen::randomly_connected_tree(n);
-    let start_node = rand::thread_rng().gen_range(1, n);
+fn benchmark_sparse_bfs(n: i64, seed: usize) {
+    let (src, dst) = datagen::randomly_connected_tree(n, seed);
+    let start_node = datagen::random_start_node(n, seed);
     let e = src.len();
-    print!("{},{},{}", n, e, datagen::graph_density(n, e));
+    print!("{},{},{},{}", seed, n, e, datagen::graph_density(n, e));
     time_bfs(unoptimised_bfs,      src.clone(), dst.clone(), start_node);
     time_bfs(preclustered_bfs,     src.clone(), dst.clone(), start_node);
     time_bfs(preclustered_rle_bfs, src.clone(), dst.clone(), start_node);
@@ -64,24 +70,57 @@ fn time_bfs<F>(mut bfs: F, src_node: Vec<i64>, dst_node: Vec<i64>, start_node: i
     print!(",{}", start.to(end));
 }
 
+// Benchmark for direction-optimising BFS
+
+// Prints to stdout valid csv lines comparing the top-down BFS over each storage method with
+// direction-optimising BFS, whose graphs also hold a table cracked on dst for bottom-up steps.
+// Both include the time to build their tables.
+pub fn benchmark_direction_optimising_bfs_csv(models: Vec<datagen::Model>, seed: usize) {
+    println!("model,seed,nodes,edges,preclustered,decomposed,underswapRLE,overswapRLE,doPreclustered,doCSR,doDecomposed,doUnderswapRLE,doOverswapRLE");
+    for model in models {
+        let (src, dst) = datagen::generate(&model, seed);
+        let start_node = datagen::random_start_node(model.nodes(), seed);
+        print!("\"{:?}\",{},{},{}", model, seed, model.nodes(), src.len());
+        time_bfs(preclustered_bfs,  src.clone(), dst.clone(), start_node);
+        time_bfs(decracked_bfs,     src.clone(), dst.clone(), start_node);
+        time_bfs(underswap_rle_bfs, src.clone(), dst.clone(), start_node);
+        time_bfs(overswap_rle_bfs,  src.clone(), dst.clone(), start_node);
+        time_direction_optimising_bfs(graph::PreclusteredGraph::new, src.clone(), dst.clone(), start_node);
+        time_direction_optimising_bfs(graph::CsrGraph::new,          src.clone(), dst.clone(), start_node);
+        time_direction_optimising_bfs(graph::DeCrackedGraph::new,    src.clone(), dst.clone(), start_node);
+        time_direction_optimising_bfs(graph::UnderswapRLEGraph::new, src.clone(), dst.clone(), start_node);
+        time_direction_optimising_bfs(graph::OverswapRLEGraph::new,  src.clone(), dst.clone(), start_node);
+        println!();
+    }
+}
+
+// Times building a graph from an adjacency list and running a direction-optimising BFS over it.
+fn time_direction_optimising_bfs<G, F>(build: F, src_node: Vec<i64>, dst_node: Vec<i64>, start_node: i64) where G: Graph, F: FnOnce(Vec<i64>, Vec<i64>) -> G {
+    let start = PreciseTime::now();
+    let mut g = build(src_node, dst_node);
+    let _visited = traversal::direction_optimising_bfs(&mut g, start_node);
+    let end = PreciseTime::now();
+    print!(",{}", start.to(end));
+}
+
 // Benchmark for a number BFS runs
 
-pub fn benchmark_sparse_bfs_csv_n_runs(runs: usize, graph_sizes: Vec<i64>) {
-    println!("nodes,edges,density,unoptimised,preclustered,preclusteredRLE,decomposed,recognitive,compactive,underswapRLE,overswapRLE");
+pub fn benchmark_sparse_bfs_csv_n_runs(runs: usize, graph_sizes: Vec<i64>, seed: usize) {
+    println!("seed,nodes,edges,density,unoptimised,preclustered,preclusteredRLE,decomposed,recognitive,compactive,underswapRLE,overswapRLE");
     for n in graph_sizes {
-        benchmark_sparse_bfs_i_runs(runs, n);
+        benchmark_sparse_bfs_i_runs(runs, n, seed);
     }
 }
 
 // For a random n node graph, runs bfs i times.
-fn benchmark_sparse_bfs_i_runs(i: usize, n: i64) {
-    let (src, dst) = datagen::randomly_connected_tree(n);
+fn benchmark_sparse_bfs_i_runs(i: usize, n: i64, seed: usize) {
+    let (src, dst) = datagen::randomly_connected_tree(n, seed);
     let mut start_nodes: Vec<i64> = Vec::new();
-    for _ in 0..i {
-        start_nodes.push(rand::thread_rng().gen_range(1, n));
+    for j in 0..i {
+        start_nodes.push(datagen::random_start_node(n, seed + j));
     }
     let e = src.len();
-    print!("{},{},{}", n, e, datagen::graph_density(n, e));
+    print!("{},{},{},{}", seed, n, e, datagen::graph_density(n, e));
     time_bfs_n_runs(unoptimised_bfs_n,      i, src.clone(), dst.clone(), start_nodes.clone());
     time_bfs_n_runs(preclustered_bfs_n,     i, src.clone(), dst.clone(), start_nodes.clone());
     time_bfs_n_runs(preclustered_rle_bfs_n, i, src.clone(), dst.clone(), start_nodes.clone());
@@ -100,6 +139,38 @@ fn time_bfs_n_runs<F>(mut bfs_n: F, n: usize, src_node: Vec<i64>, dst_node: Vec<
     print!(",{}", start.to(end));
 }
 
+// Benchmark for multi-source BFS
+
+// Prints to stdout valid csv lines comparing RUNS independent BFSs over each table with one
+// multi-source BFS from the same start nodes. Both include the time to build their tables.
+pub fn benchmark_multi_source_bfs_csv(runs: usize, graph_sizes: Vec<i64>, seed: usize) {
+    println!("seed,nodes,edges,runs,preclustered,decomposed,underswapRLE,overswapRLE,msPreclustered,msCSR,msDecomposed,msUnderswapRLE,msOverswapRLE");
+    for n in graph_sizes {
+        let (src, dst) = datagen::randomly_connected_tree(n, seed);
+        let start_nodes: Vec<i64> = (0..runs).map(|j| datagen::random_start_node(n, seed + j)).collect();
+        print!("{},{},{},{}", seed, n, src.len(), runs);
+        time_bfs_n_runs(preclustered_bfs_n,  runs, src.clone(), dst.clone(), start_nodes.clone());
+        time_bfs_n_runs(decracked_bfs_n,     runs, src.clone(), dst.clone(), start_nodes.clone());
+        time_bfs_n_runs(underswap_rle_bfs_n, runs, src.clone(), dst.clone(), start_nodes.clone());
+        time_bfs_n_runs(overswap_rle_bfs_n,  runs, src.clone(), dst.clone(), start_nodes.clone());
+        time_multi_source_bfs(graph::PreclusteredGraph::new, src.clone(), dst.clone(), &start_nodes);
+        time_multi_source_bfs(graph::CsrGraph::new,          src.clone(), dst.clone(), &start_nodes);
+        time_multi_source_bfs(graph::DeCrackedGraph::new,    src.clone(), dst.clone(), &start_nodes);
+        time_multi_source_bfs(graph::UnderswapRLEGraph::new, src.clone(), dst.clone(), &start_nodes);
+        time_multi_source_bfs(graph::OverswapRLEGraph::new,  src.clone(), dst.clone(), &start_nodes);
+        println!();
+    }
+}
+
+fn time_multi_source_bfs<G, F>(build: F, src_node: Vec<i64>, dst_node: Vec<i64>, start_nodes: &[i64]) where G: Graph, F: FnOnce(Vec<i64>, Vec<i64>) -> G {
+    let start = PreciseTime::now();
+    let mut g = build(src_node, dst_node);
+    let mut visits = 0;
+    traversal::multi_source_bfs(&mut g, start_nodes, |_, _, _| visits += 1);
+    let end = PreciseTime::now();
+    print!(",{}", start.to(end));
+}
+
 
 pub fn example_test_bfs_methods() {
     println!("Unoptimised");
@@ -149,11 +220,11 @@ pub fn bfs_example_test<F>(mut bfs: F) where F: FnMut(Vec<i64>, Vec<i64>, i64) -
     }
 }
 
-pub fn bait() {
+pub fn bait(seed: usize) {
     let n = 30 as i64;
 
-    let (src, dst) = datagen::randomly_connected_tree(n);
-    let start_node = rand::thread_rng().gen_range(1, n);
+    let (src, dst) = datagen::randomly_connected_tree(n, seed);
+    let start_node = datagen::random_start_node(n, seed);
 //    println!("let src = vec!{:?};", src);
 //    println!("let dst = vec!{:?};", dst);
 //    println!("let start_node = {};", start_node);
@@ -171,14 +242,15 @@ pub fn bait() {
         }
     }
     if failed {
-        println!("Failed!");
+        println!("Failed with seed {}!", seed);
     }
 }
 
-pub fn random_test_bfs_methods() {
+pub fn random_test_bfs_methods(seed: usize) {
     let n = 100 as i64;
-    let (src, dst) = datagen::randomly_connected_tree(n);
-    let start_node = rand::thread_rng().gen_range(1, n);
+    let (src, dst) = datagen::randomly_connected_tree(n, seed);
+    let start_node = datagen::random_start_node(n, seed);
+    println!("seed: {}", seed);
     println!("src: {:?}", src);
     println!("dst: {:?}", dst);
 
@@ -220,20 +292,39 @@ pub fn bfs_random_test<F>(mut bfs: F, n: i64, src: Vec<i64>, dst: Vec<i64>, star
     }
 }
 
+// The position of vertex V in a visited bit vector.
+fn position(v: i64) -> usize {
+    if v < 1 {
+        panic!("position: Vertex {} is not a dense ID", v);
+    }
+    (v - 1) as usize
+}
+
+fn visited_from(start_node: i64) -> BitVec {
+    BitVec::from_elem(1 + position(start_node), false)
+}
+
+// Marks DST visited as soon as it's discovered, so that the frontier never holds it twice.
 fn discover(dst: i64, visited: &mut BitVec, frontier: &mut Vec<i64>) {
-    if !visited.get((dst as usize) - 1).unwrap_or(false) && !frontier.contains(&dst) {
+    let i = position(dst);
+    if i >= visited.len() {
+        let l = visited.len();
+        visited.grow(1 + i - l, false);
+    }
+    if !visited.get(i).unwrap() {
+        visited.set(i, true);
         frontier.push(dst);
     }
 }
 
-fn set_indices(bv: &mut BitVec, indices: Vec<i64>) {
+fn set_visited(bv: &mut BitVec, vertices: &[i64]) {
     let l = bv.len();
-    for i in indices {
-        let i_usize = i as usize;
-        if i_usize >= l {
-            bv.grow(1 + i_usize - l, false);
+    for &v in vertices {
+        let i = position(v);
+        if i >= l {
+            bv.grow(1 + i - l, false);
         }
-        bv.set(i_usize, true);
+        bv.set(i, true);
     }
 }
 
@@ -247,17 +338,26 @@ fn bv_where(bv: BitVec) -> Vec<i64> {
     v
 }
 
-fn indicise(v: Vec<i64>) -> Vec<i64> {
-    v.iter().map(|x|x-1).collect()
+// Runs BFS over the graph renumbered densely, and decodes the nodes it visited.
+fn densely<F>(src_node: Vec<i64>, dst_node: Vec<i64>, start_node: i64, bfs: F) -> Vec<i64> where F: FnOnce(Vec<i64>, Vec<i64>, i64) -> Vec<i64> {
+    let (mut dictionary, src, dst) = vertex_dictionary::densify(&src_node, &dst_node);
+    let start = dictionary.intern(start_node);
+    dictionary.decode(&bfs(src, dst, start))
+}
+
+fn densely_n<F>(src_node: Vec<i64>, dst_node: Vec<i64>, start_nodes: Vec<i64>, bfs_n: F) where F: FnOnce(Vec<i64>, Vec<i64>, Vec<i64>) {
+    let (mut dictionary, src, dst) = vertex_dictionary::densify(&src_node, &dst_node);
+    let starts = start_nodes.iter().map(|&v| dictionary.intern(v)).collect();
+    bfs_n(src, dst, starts)
 }
 
 // Naive/Unoptimised
 fn unoptimised_bfs_refs(src_node: &Vec<i64>, dst_node: &Vec<i64>, start_node: i64) -> Vec<i64> {
     let mut frontier = vec![start_node];
-    let mut visited = BitVec::from_elem(start_node as usize, false);
+    let mut visited = visited_from(start_node);
 
     while !frontier.is_empty() {
-        set_indices(&mut visited, indicise(frontier.clone()));
+        set_visited(&mut visited, &frontier);
 
         let prev_frontier = frontier.clone();
         frontier.clear();
@@ -271,13 +371,15 @@ fn unoptimised_bfs_refs(src_node: &Vec<i64>, dst_node: &Vec<i64>, start_node: i6
     }
     bv_where(visited)
 }
-fn unoptimised_bfs(src_node: Vec<i64>, dst_node: Vec<i64>, start_node: i64) -> Vec<i64> {
-    unoptimised_bfs_refs(&src_node, &dst_node, start_node)
+pub fn unoptimised_bfs(src_node: Vec<i64>, dst_node: Vec<i64>, start_node: i64) -> Vec<i64> {
+    densely(src_node, dst_node, start_node, |src, dst, start| unoptimised_bfs_refs(&src, &dst, start))
 }
 fn unoptimised_bfs_n(n: usize, src_node: Vec<i64>, dst_node: Vec<i64>, start_nodes: Vec<i64>) {
-    for i in 0..n {
-        unoptimised_bfs_refs(&src_node, &dst_node, start_nodes[i]);
-    }
+    densely_n(src_node, dst_node, start_nodes, |src, dst, starts| {
+        for &start in &starts[..n] {
+            unoptimised_bfs_refs(&src, &dst, start);
+        }
+    })
 }
 
 // Preclustered
@@ -301,10 +403,10 @@ pub fn precluster(src_node: &Vec<i64>, dst_node: &Vec<i64>) -> (Vec<i64>, Vec<i6
 }
 fn preclustered_bfs_from_ready(src_col: &Vec<i64>, dst_col: &Vec<i64>, start_node: i64) -> Vec<i64> {
     let mut frontier = vec![start_node];
-    let mut visited = BitVec::from_elem(start_node as usize, false);
+    let mut visited = visited_from(start_node);
 
     while !frontier.is_empty() {
-        set_indices(&mut visited, indicise(frontier.clone()));
+        set_visited(&mut visited, &frontier);
 
         let prev_frontier = frontier.clone();
         frontier.clear();
@@ -337,15 +439,19 @@ fn preclustered_bfs_from_ready(src_col: &Vec<i64>, dst_col: &Vec<i64>, start_nod
     }
     bv_where(visited)
 }
-fn preclustered_bfs(src_node: Vec<i64>, dst_node: Vec<i64>, start_node: i64) -> Vec<i64> {
-    let (src_col, dst_col) = precluster(&src_node, &dst_node);
-    preclustered_bfs_from_ready(&src_col, &dst_col, start_node)
+pub fn preclustered_bfs(src_node: Vec<i64>, dst_node: Vec<i64>, start_node: i64) -> Vec<i64> {
+    densely(src_node, dst_node, start_node, |src, dst, start| {
+        let (src_col, dst_col) = precluster(&src, &dst);
+        preclustered_bfs_from_ready(&src_col, &dst_col, start)
+    })
 }
 fn preclustered_bfs_n(n: usize, src_node: Vec<i64>, dst_node: Vec<i64>, start_nodes: Vec<i64>) {
-    let (src_col, dst_col) = precluster(&src_node, &dst_node);
-    for i in 0..n {
-        preclustered_bfs_from_ready(&src_col, &dst_col, start_nodes[i]);
-    }
+    densely_n(src_node, dst_node, start_nodes, |src, dst, starts| {
+        let (src_col, dst_col) = precluster(&src, &dst);
+        for &start in &starts[..n] {
+            preclustered_bfs_from_ready(&src_col, &dst_col, start);
+        }
+    })
 }
 
 // Preclustered RLE
@@ -370,44 +476,53 @@ fn precluster_and_rle(src_node: Vec<i64>, dst_node: Vec<i64>) -> Vec<Vec<i64>> {
 }
 fn preclustered_rle_bfs_from_ready(encoded_col: &Vec<Vec<i64>>, start_node: i64) -> Vec<i64> {
     let mut frontier = vec![start_node];
-    let mut visited = BitVec::from_elem(start_node as usize, false);
+    let mut visited = visited_from(start_node);
 
     while !frontier.is_empty() {
-        set_indices(&mut visited, indicise(frontier.clone()));
+        set_visited(&mut visited, &frontier);
 
         let prev_frontier = frontier.clone();
         frontier.clear();
         for src in prev_frontier {
-            for dst in &encoded_col[src as usize] {
-                discover(*dst, &mut visited, &mut frontier);
+            // A start node without out-edges may be past the end of the column.
+            if let Some(dsts) = encoded_col.get(src as usize) {
+                for dst in dsts {
+                    discover(*dst, &mut visited, &mut frontier);
+                }
             }
         }
     }
     bv_where(visited)
 }
-fn preclustered_rle_bfs(src_node: Vec<i64>, dst_node: Vec<i64>, start_node: i64) -> Vec<i64> {
-    let encoded_col = precluster_and_rle(src_node, dst_node);
-    preclustered_rle_bfs_from_ready(&encoded_col, start_node)
+pub fn preclustered_rle_bfs(src_node: Vec<i64>, dst_node: Vec<i64>, start_node: i64) -> Vec<i64> {
+    densely(src_node, dst_node, start_node, |src, dst, start| {
+        let encoded_col = precluster_and_rle(src, dst);
+        preclustered_rle_bfs_from_ready(&encoded_col, start)
+    })
 }
 fn preclustered_rle_bfs_n(n: usize, src_node: Vec<i64>, dst_node: Vec<i64>, start_nodes: Vec<i64>) {
-    let encoded_col = precluster_and_rle(src_node, dst_node);
-    for i in 0..n {
-        preclustered_rle_bfs_from_ready(&encoded_col, start_nodes[i]);
-    }
+    densely_n(src_node, dst_node, start_nodes, |src, dst, starts| {
+        let encoded_col = precluster_and_rle(src, dst);
+        for &start in &starts[..n] {
+            preclustered_rle_bfs_from_ready(&encoded_col, start);
+        }
+    })
 }
 
 // Decomposed cracking
-fn decracked_bfs(src_node: Vec<i64>, dst_node: Vec<i64>, start_node: i64) -> Vec<i64> {
-    let mut adjacency_list = decomposed_cracking::from_adjacency_vectors(src_node, dst_node, "src");
-    decracked_bfs_adjl(&mut adjacency_list, start_node)
+pub fn decracked_bfs(src_node: Vec<i64>, dst_node: Vec<i64>, start_node: i64) -> Vec<i64> {
+    densely(src_node, dst_node, start_node, |src, dst, start| {
+        let mut adjacency_list = decomposed_cracking::from_adjacency_vectors(src, dst, "src");
+        decracked_bfs_adjl(&mut adjacency_list, start)
+    })
 }
 pub fn decracked_bfs_adjl(adjacency_list: &mut decomposed_cracking::DeCrackedTable, start_node: i64) -> Vec<i64> {
     let mut frontier = vec![start_node];
-    let mut visited = BitVec::from_elem(start_node as usize, false);
+    let mut visited = visited_from(start_node);
 
     while !frontier.is_empty() {
         // Add visited nodes
-        set_indices(&mut visited, indicise(frontier.clone()));
+        set_visited(&mut visited, &frontier);
 
         let prev_frontier = frontier.clone();
         frontier.clear();
@@ -423,24 +538,28 @@ pub fn decracked_bfs_adjl(adjacency_list: &mut decomposed_cracking::DeCrackedTab
     bv_where(visited)
 }
 fn decracked_bfs_n(n: usize, src_node: Vec<i64>, dst_node: Vec<i64>, start_nodes: Vec<i64>) {
-    let mut adjacency_list = decomposed_cracking::from_adjacency_vectors(src_node, dst_node, "src");
-    for i in 0..n {
-        decracked_bfs_adjl(&mut adjacency_list, start_nodes[i]);
-    }
+    densely_n(src_node, dst_node, start_nodes, |src, dst, starts| {
+        let mut adjacency_list = decomposed_cracking::from_adjacency_vectors(src, dst, "src");
+        for &start in &starts[..n] {
+            decracked_bfs_adjl(&mut adjacency_list, start);
+        }
+    })
 }
 
 // Recognitive compression
-fn reco_bfs(src_node: Vec<i64>, dst_node: Vec<i64>, start_node: i64) -> Vec<i64> {
-    let mut adjacency_list = recognitive_compression::from_adjacency_vectors(src_node, dst_node, "src");
-    reco_bfs_adjl(&mut adjacency_list, start_node)
+pub fn reco_bfs(src_node: Vec<i64>, dst_node: Vec<i64>, start_node: i64) -> Vec<i64> {
+    densely(src_node, dst_node, start_node, |src, dst, start| {
+        let mut adjacency_list = recognitive_compression::from_adjacency_vectors(src, dst, "src");
+        reco_bfs_adjl(&mut adjacency_list, start)
+    })
 }
 fn reco_bfs_adjl(adjacency_list: &mut recognitive_compression::ReCoTable, start_node: i64) -> Vec<i64> {
     let mut frontier = vec![start_node];
-    let mut visited = BitVec::from_elem(start_node as usize, false);
+    let mut visited = visited_from(start_node);
 
     while !frontier.is_empty() {
         // Add visited nodes
-        set_indices(&mut visited, indicise(frontier.clone()));
+        set_visited(&mut visited, &frontier);
 
         let prev_frontier = frontier.clone();
         frontier.clear();
@@ -456,24 +575,28 @@ fn reco_bfs_adjl(adjacency_list: &mut recognitive_compression::ReCoTable, start_
     bv_where(visited)
 }
 fn reco_bfs_n(n: usize, src_node: Vec<i64>, dst_node: Vec<i64>, start_nodes: Vec<i64>) {
-    let mut adjacency_list = recognitive_compression::from_adjacency_vectors(src_node, dst_node, "src");
-    for i in 0..n {
-        reco_bfs_adjl(&mut adjacency_list, start_nodes[i]);
-    }
+    densely_n(src_node, dst_node, start_nodes, |src, dst, starts| {
+        let mut adjacency_list = recognitive_compression::from_adjacency_vectors(src, dst, "src");
+        for &start in &starts[..n] {
+            reco_bfs_adjl(&mut adjacency_list, start);
+        }
+    })
 }
 
 // Compactive compression
-fn coco_bfs(src_node: Vec<i64>, dst_node: Vec<i64>, start_node: i64) -> Vec<i64> {
-    let mut adjacency_list = compactive_compression::from_adjacency_vectors(src_node, dst_node, "src");
-    coco_bfs_adjl(&mut adjacency_list, start_node)
+pub fn coco_bfs(src_node: Vec<i64>, dst_node: Vec<i64>, start_node: i64) -> Vec<i64> {
+    densely(src_node, dst_node, start_node, |src, dst, start| {
+        let mut adjacency_list = compactive_compression::from_adjacency_vectors(src, dst, "src");
+        coco_bfs_adjl(&mut adjacency_list, start)
+    })
 }
 fn coco_bfs_adjl(adjacency_list: &mut compactive_compression::CoCoTable, start_node: i64) -> Vec<i64> {
     let mut frontier = vec![start_node];
-    let mut visited = BitVec::from_elem(start_node as usize, false);
+    let mut visited = visited_from(start_node);
 
     while !frontier.is_empty() {
         // Add visited nodes
-        set_indices(&mut visited, indicise(frontier.clone()));
+        set_visited(&mut visited, &frontier);
 
         let prev_frontier = frontier.clone();
         frontier.clear();
@@ -489,25 +612,29 @@ fn coco_bfs_adjl(adjacency_list: &mut compactive_compression::CoCoTable, start_n
     bv_where(visited)
 }
 fn coco_bfs_n(n: usize, src_node: Vec<i64>, dst_node: Vec<i64>, start_nodes: Vec<i64>) {
-    let mut adjacency_list = compactive_compression::from_adjacency_vectors(src_node, dst_node, "src");
-    for i in 0..n {
-        coco_bfs_adjl(&mut adjacency_list, start_nodes[i]);
-    }
+    densely_n(src_node, dst_node, start_nodes, |src, dst, starts| {
+        let mut adjacency_list = compactive_compression::from_adjacency_vectors(src, dst, "src");
+        for &start in &starts[..n] {
+            coco_bfs_adjl(&mut adjacency_list, start);
+        }
+    })
 }
 
 
 // Underswap-RLE compression
-fn underswap_rle_bfs(src_node: Vec<i64>, dst_node: Vec<i64>, start_node: i64) -> Vec<i64> {
-    let mut adjacency_list = underswap_rle_compression::from_adjacency_vectors(src_node, dst_node, "src");
-    underswap_rle_bfs_adjl(&mut adjacency_list, start_node)
+pub fn underswap_rle_bfs(src_node: Vec<i64>, dst_node: Vec<i64>, start_node: i64) -> Vec<i64> {
+    densely(src_node, dst_node, start_node, |src, dst, start| {
+        let mut adjacency_list = underswap_rle_compression::from_adjacency_vectors(src, dst, "src");
+        underswap_rle_bfs_adjl(&mut adjacency_list, start)
+    })
 }
 pub fn underswap_rle_bfs_adjl(adjacency_list: &mut underswap_rle_compression::UnderswapRLETable, start_node: i64) -> Vec<i64> {
     let mut frontier = vec![start_node];
-    let mut visited = BitVec::from_elem(start_node as usize, false);
+    let mut visited = visited_from(start_node);
 
     while !frontier.is_empty() {
         // Add visited nodes
-        set_indices(&mut visited, indicise(frontier.clone()));
+        set_visited(&mut visited, &frontier);
 
         let prev_frontier = frontier.clone();
         frontier.clear();
@@ -523,24 +650,28 @@ pub fn underswap_rle_bfs_adjl(adjacency_list: &mut underswap_rle_compression::Un
     bv_where(visited)
 }
 fn underswap_rle_bfs_n(n: usize, src_node: Vec<i64>, dst_node: Vec<i64>, start_nodes: Vec<i64>) {
-    let mut adjacency_list = underswap_rle_compression::from_adjacency_vectors(src_node, dst_node, "src");
-    for i in 0..n {
-        underswap_rle_bfs_adjl(&mut adjacency_list, start_nodes[i]);
-    }
+    densely_n(src_node, dst_node, start_nodes, |src, dst, starts| {
+        let mut adjacency_list = underswap_rle_compression::from_adjacency_vectors(src, dst, "src");
+        for &start in &starts[..n] {
+            underswap_rle_bfs_adjl(&mut adjacency_list, start);
+        }
+    })
 }
 
 // Overswap-RLE compression
-fn overswap_rle_bfs(src_node: Vec<i64>, dst_node: Vec<i64>, start_node: i64) -> Vec<i64> {
-    let mut adjacency_list = overswap_rle_compression::from_adjacency_vectors(src_node, dst_node, "src");
-    overswap_rle_bfs_adjl(&mut adjacency_list, start_node)
+pub fn overswap_rle_bfs(src_node: Vec<i64>, dst_node: Vec<i64>, start_node: i64) -> Vec<i64> {
+    densely(src_node, dst_node, start_node, |src, dst, start| {
+        let mut adjacency_list = overswap_rle_compression::from_adjacency_vectors(src, dst, "src");
+        overswap_rle_bfs_adjl(&mut adjacency_list, start)
+    })
 }
 pub fn overswap_rle_bfs_adjl(adjacency_list: &mut overswap_rle_compression::OverswapRLETable, start_node: i64) -> Vec<i64> {
     let mut frontier = vec![start_node];
-    let mut visited = BitVec::from_elem(start_node as usize, false);
+    let mut visited = visited_from(start_node);
 
     while !frontier.is_empty() {
         // Add visited nodes
-        set_indices(&mut visited, indicise(frontier.clone()));
+        set_visited(&mut visited, &frontier);
 
         let prev_frontier = frontier.clone();
         frontier.clear();
@@ -556,10 +687,12 @@ pub fn overswap_rle_bfs_adjl(adjacency_list: &mut overswap_rle_compression::Over
     bv_where(visited)
 }
 fn overswap_rle_bfs_n(n: usize, src_node: Vec<i64>, dst_node: Vec<i64>, start_nodes: Vec<i64>) {
-    let mut adjacency_list = overswap_rle_compression::from_adjacency_vectors(src_node, dst_node, "src");
-    for i in 0..n {
-        overswap_rle_bfs_adjl(&mut adjacency_list, start_nodes[i]);
-    }
+    densely_n(src_node, dst_node, start_nodes, |src, dst, starts| {
+        let mut adjacency_list = overswap_rle_compression::from_adjacency_vectors(src, dst, "src");
+        for &start in &starts[..n] {
+            overswap_rle_bfs_adjl(&mut adjacency_list, start);
+        }
+    })
 }
 
 // == BREAK-EVEN POINT ==
@@ -567,12 +700,12 @@ fn overswap_rle_bfs_n(n: usize, src_node: Vec<i64>, dst_node: Vec<i64>, start_no
 
 pub fn decracked_bfs_adjl_until(mut adjacency_list: decomposed_cracking::DeCrackedTable, start_node: i64, d: Duration) -> usize {
     let mut frontier = vec![start_node];
-    let mut visited = BitVec::from_elem(start_node as usize, false);
+    let mut visited = visited_from(start_node);
 
     let start = PreciseTime::now();
     let mut queries = 0;
     while !frontier.is_empty() {
-        set_indices(&mut visited, indicise(frontier.clone()));
+        set_visited(&mut visited, &frontier);
 
         let prev_frontier = frontier.clone();
         frontier.clear();
@@ -592,12 +725,12 @@ pub fn decracked_bfs_adjl_until(mut adjacency_list: decomposed_cracking::DeCrack
 
 pub fn reco_bfs_adjl_until(mut adjacency_list: recognitive_compression::ReCoTable, start_node: i64, d: Duration) -> usize {
     let mut frontier = vec![start_node];
-    let mut visited = BitVec::from_elem(start_node as usize, false);
+    let mut visited = visited_from(start_node);
 
     let start = PreciseTime::now();
     let mut queries = 0;
     while !frontier.is_empty() {
-        set_indices(&mut visited, indicise(frontier.clone()));
+        set_visited(&mut visited, &frontier);
 
         let prev_frontier = frontier.clone();
         frontier.clear();
@@ -617,12 +750,12 @@ pub fn reco_bfs_adjl_until(mut adjacency_list: recognitive_compression::ReCoTabl
 
 pub fn coco_bfs_adjl_until(mut adjacency_list: compactive_compression::CoCoTable, start_node: i64, d: Duration) -> usize {
     let mut frontier = vec![start_node];
-    let mut visited = BitVec::from_elem(start_node as usize, false);
+    let mut visited = visited_from(start_node);
 
     let start = PreciseTime::now();
     let mut queries = 0;
     while !frontier.is_empty() {
-        set_indices(&mut visited, indicise(frontier.clone()));
+        set_visited(&mut visited, &frontier);
 
         let prev_frontier = frontier.clone();
         frontier.clear();
@@ -642,12 +775,12 @@ pub fn coco_bfs_adjl_until(mut adjacency_list: compactive_compression::CoCoTable
 
 pub fn underswap_bfs_adjl_until(mut adjacency_list: underswap_rle_compression::UnderswapRLETable, start_node: i64, d: Duration) -> usize {
     let mut frontier = vec![start_node];
-    let mut visited = BitVec::from_elem(start_node as usize, false);
+    let mut visited = visited_from(start_node);
 
     let start = PreciseTime::now();
     let mut queries = 0;
     while !frontier.is_empty() {
-        set_indices(&mut visited, indicise(frontier.clone()));
+        set_visited(&mut visited, &frontier);
 
         let prev_frontier = frontier.clone();
         frontier.clear();
@@ -667,12 +800,12 @@ pub fn underswap_bfs_adjl_until(mut adjacency_list: underswap_rle_compression::U
 
 pub fn overswap_bfs_adjl_until(mut adjacency_list: overswap_rle_compression::OverswapRLETable, start_node: i64, d: Duration) -> usize {
     let mut frontier = vec![start_node];
-    let mut visited = BitVec::from_elem(start_node as usize, false);
+    let mut visited = visited_from(start_node);
 
     let start = PreciseTime::now();
     let mut queries = 0;
     while !frontier.is_empty() {
-        set_indices(&mut visited, indicise(frontier.clone()));
+        set_visited(&mut visited, &frontier);
 
         let prev_frontier = frontier.clone();
         frontier.clear();
@@ -17,10 +17,24 @@ pub mod underswap_rle_compression;
 pub mod overswap_rle_compression;
 
 pub mod datagen;
+pub mod workload;
 pub mod bfs;
 pub mod pagerank;
 
+pub mod datasets;
 pub mod load_person_csv;
+pub mod load_ldbc;
+pub mod synthetic_ldbc;
+pub mod vertex_dictionary;
+pub mod export;
+pub mod graph;
+pub mod traversal;
+pub mod sssp;
+pub mod components;
+pub mod centrality;
+pub mod triangles;
+pub mod ranking;
+pub mod load_graph;
 pub mod personrank;
 pub mod quicksort;
 
@@ -28,20 +42,37 @@ use time::PreciseTime;
 use time::Duration;
 use rand::Rng;
 
+use std::collections::HashMap;
+use std::env;
+
+// The seed is the first argument, or 0 if there isn't one, so that a run can be replayed. The
+// second names the benchmark to run, which is traversal if there isn't one.
 fn main() {
-    traversal_time(2000, 10);
+    let seed = match env::args().nth(1) {
+        Some(s) => s.parse().unwrap_or_else(|_| panic!("main: Seed {} is not a number", s)),
+        None    => 0,
+    };
+    match env::args().nth(2).as_deref() {
+        None | Some("traversal") => traversal_time(&datagen::Model::Tree(2000), 10, seed),
+        Some("betweenness")      => betweenness_time(load_person_csv::PersonSource::Synthetic(0.1, seed), 100, seed),
+        Some("incremental")      => incremental_pagerank_time(load_person_csv::PersonSource::Synthetic(0.1, seed), 100, seed),
+        Some(b)                  => panic!("main: There is no benchmark called {}", b),
+    }
 }
 
-fn traversal_time(n: i64, i: usize) {
+// Graph j of the (i) graphs is generated from seed + j.
+fn traversal_time(model: &datagen::Model, i: usize, seed: usize) {
+    let n = model.nodes();
+    println!("{:?}, seed: {}", model, seed);
     let mut decracked_times = Vec::new();
     let mut underswap_times = Vec::new();
     let mut overswap_times = Vec::new();
 
     for j in 0..i {
         let mut start = PreciseTime::now();
-        let (src, dst) = datagen::randomly_connected_tree(n);
-        let start_node = rand::thread_rng().gen_range(1, n);
-         shellsprintln!("Created tree {} after {} seconds", j, start.to(PreciseTime::now()).to_string());
+        let (src, dst) = datagen::generate(model, seed + j);
+        let start_node = datagen::random_start_node(n, seed + j);
+        println!("Created graph {} after {} seconds", j, start.to(PreciseTime::now()));
 
         start = PreciseTime::now();
         bfs::decracked_bfs_adjl(&mut decomposed_cracking::from_adjacency_vectors(src.clone(), dst.clone(), "src"), start_node);
@@ -65,8 +96,11 @@ fn traversal_time(n: i64, i: usize) {
     println!("Overswap:  {}", overswap_avg);
 }
 
-// Gets for each method the average over (i) runs of the break-even point on a random tree of size (n).
-fn break_even_points(n: i64, i: usize) {
+// Gets for each method the average over (i) runs of the break-even point on a random graph of the
+// given model. Graph j of the (i) graphs is generated from seed + j.
+fn break_even_points(model: &datagen::Model, i: usize, seed: usize) {
+    let n = model.nodes();
+    println!("{:?}, seed: {}", model, seed);
     let mut decracked_queries = Vec::new();
     let mut reco_queries = Vec::new();
     let mut coco_queries = Vec::new();
@@ -75,9 +109,9 @@ fn break_even_points(n: i64, i: usize) {
 
     for j in 0..i {
         let start = PreciseTime::now();
-        let (src, dst) = datagen::randomly_connected_tree(n);
-        let start_node = rand::thread_rng().gen_range(1, n);
-        println!("Created tree {} after {} seconds", j, start.to(PreciseTime::now()).to_string());
+        let (src, dst) = datagen::generate(model, seed + j);
+        let start_node = datagen::random_start_node(n, seed + j);
+        println!("Created graph {} after {} seconds", j, start.to(PreciseTime::now()));
 
         let start = PreciseTime::now();
         bfs::precluster(&src, &dst);
@@ -97,39 +131,95 @@ fn break_even_points(n: i64, i: usize) {
     println!("Overswap:  {}", overswap_queries.iter().fold(0 as f64, |sum, val| sum + (*val  as f64)) / (i as f64));
 }
 
-fn prep_graphviz(src: Vec<i64>, dst: Vec<i64>) {
-    print!("digraph {} ", "{");
-    for i in 0..src.len() {
-        print!("{} -> {}; ", src[i], dst[i]);
+// Times sampled betweenness of the people in SOURCE, over their friendships in both directions,
+// on each storage method, including the time to build each graph. Prints the most central people.
+fn betweenness_time(source: load_person_csv::PersonSource, samples: usize, seed: usize) {
+    println!("Betweenness from {} samples on {:?}, seed: {}", samples, source, seed);
+    let (_, (src, dst)) = load_person_csv::load_people(source);
+    let both_src: Vec<i64> = src.iter().chain(dst.iter()).cloned().collect();
+    let both_dst: Vec<i64> = dst.iter().chain(src.iter()).cloned().collect();
+
+    time_betweenness("Preclustered", graph::PreclusteredGraph::new, &both_src, &both_dst, samples, seed);
+    time_betweenness("CSR",          graph::CsrGraph::new,          &both_src, &both_dst, samples, seed);
+    time_betweenness("Decracked",    graph::DeCrackedGraph::new,    &both_src, &both_dst, samples, seed);
+    time_betweenness("Underswap",    graph::UnderswapRLEGraph::new, &both_src, &both_dst, samples, seed);
+    let centrality = time_betweenness("Overswap", graph::OverswapRLEGraph::new, &both_src, &both_dst, samples, seed);
+
+    let mut ranked: Vec<(i64, f64)> = centrality.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+    for (person, c) in ranked.into_iter().take(5) {
+        println!("Person {}: {}", person, c);
+    }
+}
+
+fn time_betweenness<G, F>(name: &str, build: F, src: &[i64], dst: &[i64], samples: usize, seed: usize) -> HashMap<i64, f64> where G: graph::Graph, F: FnOnce(Vec<i64>, Vec<i64>) -> G {
+    let start = PreciseTime::now();
+    let mut g = build(src.to_vec(), dst.to_vec());
+    let centrality = centrality::sampled_betweenness(&mut g, samples, seed);
+    println!("{:<14}{}", format!("{}:", name), start.to(PreciseTime::now()));
+    centrality
+}
+
+// Times updating PageRank of the people in SOURCE after a batch of CHANGES random new friendships,
+// against recomputing it, on each storage method. Each graph is built with the new friendships.
+fn incremental_pagerank_time(source: load_person_csv::PersonSource, changes: usize, seed: usize) {
+    println!("Incremental PageRank after {} insertions on {:?}, seed: {}", changes, source, seed);
+    let (people, (mut src, mut dst)) = load_person_csv::load_people(source);
+    let mut config = ranking::PageRankConfig::new();
+    config.vertices = people.iter().map(|p| p.id).collect();
+    let previous = personrank::preclustered_personrank(config.vertices.clone(), src.clone(), dst.clone(), &config).ranks;
+
+    let mut rng = datagen::seeded_rng(seed);
+    let mut batch = Vec::new();
+    for _ in 0..changes {
+        let (u, v) = (*rng.choose(&config.vertices).unwrap(), *rng.choose(&config.vertices).unwrap());
+        src.push(u);
+        dst.push(v);
+        batch.push(ranking::EdgeChange::Insert(u, v));
     }
-    print!("{}\n", "}");
+
+    time_incremental_pagerank("Preclustered", graph::PreclusteredGraph::new, &src, &dst, &previous, &batch, &config);
+    time_incremental_pagerank("CSR",          graph::CsrGraph::new,          &src, &dst, &previous, &batch, &config);
+    time_incremental_pagerank("Decracked",    graph::DeCrackedGraph::new,    &src, &dst, &previous, &batch, &config);
+    time_incremental_pagerank("Reco",         graph::ReCoGraph::new,         &src, &dst, &previous, &batch, &config);
+    time_incremental_pagerank("Coco",         graph::CoCoGraph::new,         &src, &dst, &previous, &batch, &config);
+    time_incremental_pagerank("Underswap",    graph::UnderswapRLEGraph::new, &src, &dst, &previous, &batch, &config);
+    time_incremental_pagerank("Overswap",     graph::OverswapRLEGraph::new,  &src, &dst, &previous, &batch, &config);
 }
 
-fn speed_test(sf: i16, mi: i16, n: i8) {
-    println!("Speed test over {} iterations", n);
+fn time_incremental_pagerank<G, F>(name: &str, build: F, src: &[i64], dst: &[i64], previous: &HashMap<i64, f64>, batch: &[ranking::EdgeChange], config: &ranking::PageRankConfig) where G: graph::Graph, F: Fn(Vec<i64>, Vec<i64>) -> G {
+    // Updating needs the graph built afresh too, so its build counts towards it.
+    let start = PreciseTime::now();
+    let mut g = build(src.to_vec(), dst.to_vec());
+    let build_time = start.to(PreciseTime::now());
+    let updated = ranking::update(&mut g, previous, batch, config);
+    let recomputed = ranking::build_and_run(build, src.to_vec(), dst.to_vec(), config);
+    let error = recomputed.ranks.iter().map(|(v, r)| (updated.ranks[v] - r).abs()).sum::<f64>();
+    println!("{:<14}update: build {}, setup {}, compute {} in {} rounds; recompute: setup {}, compute {} in {} iterations; L1 difference {}",
+             format!("{}:", name), build_time, updated.setup_time, updated.compute_time, updated.iterations,
+             recomputed.setup_time, recomputed.compute_time, recomputed.iterations, error);
+}
+
+fn speed_test(source: load_person_csv::PersonSource, config: &ranking::PageRankConfig, n: i8) {
+    println!("Speed test over {} iterations on {:?}", n, source);
     let mut diffs: Vec<Duration> = Vec::new();
 
-    let (people, (src, dst)) = match sf {
-        1 => (load_person_csv::sf1_nodes(), load_person_csv::sf1_edges_adjl()),
-        3 => (load_person_csv::sf3_nodes(), load_person_csv::sf3_edges_adjl()),
-        10 => (load_person_csv::sf10_nodes(), load_person_csv::sf10_edges_adjl()),
-        _ => panic!("No data for scale_factor: {}", sf),
-    };
+    let (people, (src, dst)) = load_person_csv::load_people(source);
     let vertices: Vec<i64> = people.iter().map(|p|p.id).collect();
 
     for _ in 0..n {
-        let (preclustered_ranks, _) = personrank::preclustered_personrank(vertices.clone(), src.clone(), dst.clone(), mi);
-        let (decracked_ranks, dt) = personrank::decracked_personrank(vertices.clone(), src.clone(), dst.clone(), mi);
-        let (reco_ranks, ut) = personrank::reco_personrank(vertices.clone(), src.clone(), dst.clone(), mi);
+        let preclustered = personrank::preclustered_personrank(vertices.clone(), src.clone(), dst.clone(), config);
+        let decracked = personrank::decracked_personrank(vertices.clone(), src.clone(), dst.clone(), config);
+        let reco = personrank::reco_personrank(vertices.clone(), src.clone(), dst.clone(), config);
 
-        let diff = dt - ut;
+        let diff = decracked.compute_time - reco.compute_time;
         diffs.push(diff);
 
         let epsilon = 0.00001;
 
-        for (k, v) in &preclustered_ranks {
-            assert!((*v - decracked_ranks[k]).abs() < epsilon);
-            assert!((*v - reco_ranks[k]).abs() < epsilon);
+        for (k, v) in &preclustered.ranks {
+            assert!((*v - decracked.ranks[k]).abs() < epsilon);
+            assert!((*v - reco.ranks[k]).abs() < epsilon);
         }
     }
 
@@ -141,8 +231,8 @@ fn speed_test(sf: i16, mi: i16, n: i8) {
     println!("===");
 }
 
-fn speed_test_tighten(selectivity: f64, n: usize) {
-    let mut rng = rand::thread_rng();
+fn speed_test_tighten(selectivity: f64, n: usize, seed: usize) {
+    let mut rng = datagen::seeded_rng(seed);
     let p_low = 0;
 
     let mut crk = Vec::new();
@@ -158,7 +248,7 @@ fn speed_test_tighten(selectivity: f64, n: usize) {
     }
 
     let x = 1;
-    print!("Selecting {} from {}, {} times: ", x, v, n);
+    print!("Selecting {} from {}, {} times with seed {}: ", x, v, n, seed);
 
     let mut ts: Vec<Duration> = Vec::new();
     for _ in 0..n {
@@ -0,0 +1,81 @@
+// Betweenness centrality
+// Brandes' algorithm (Papers/brandesalgorithm-Brandes-01.pdf) over any Graph. It runs a BFS from
+// every vertex, counting shortest paths on the way out, then accumulates each vertex's dependency on
+// the way back. The sampled version runs it from a random subset of the vertices and scales up,
+// which estimates betweenness for graphs too large for the exact version.
+// Graphs are taken as directed, so an undirected graph stored as edges in both directions gets
+// twice its usual betweenness.
+
+use rand::Rng;
+
+use std::collections::HashMap;
+
+use datagen;
+use graph::Graph;
+
+pub fn betweenness<G: Graph + ?Sized>(graph: &mut G) -> HashMap<i64, f64> {
+    let vertices = graph.vertices().to_vec();
+    let sources: Vec<usize> = (0..vertices.len()).collect();
+    brandes(graph, &vertices, &sources, 1.0)
+}
+
+// Estimates betweenness from BFSs from SAMPLES vertices, chosen without replacement from the seed.
+pub fn sampled_betweenness<G: Graph + ?Sized>(graph: &mut G, samples: usize, seed: usize) -> HashMap<i64, f64> {
+    let vertices = graph.vertices().to_vec();
+    let mut sources: Vec<usize> = (0..vertices.len()).collect();
+    datagen::seeded_rng(seed).shuffle(&mut sources);
+    sources.truncate(samples);
+    let scale = if sources.is_empty() { 0.0 } else { vertices.len() as f64 / sources.len() as f64 };
+    brandes(graph, &vertices, &sources, scale)
+}
+
+// Accumulates the dependencies of every vertex on BFSs from the vertices at positions SOURCES,
+// scaled by SCALE.
+fn brandes<G: Graph + ?Sized>(graph: &mut G, vertices: &[i64], sources: &[usize], scale: f64) -> HashMap<i64, f64> {
+    let n = vertices.len();
+    let mut centrality = vec![0.0; n];
+
+    let mut depth = vec![usize::MAX; n];
+    let mut paths = vec![0.0; n];
+    let mut dependency = vec![0.0; n];
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for &s in sources {
+        // Vertices in the order the BFS reached them.
+        let mut order = vec![s];
+        depth[s] = 0;
+        paths[s] = 1.0;
+        let mut i = 0;
+        while i < order.len() {
+            let v = order[i];
+            // Every BFS looks up its vertices' neighbours again, which is what cracking adapts to.
+            for u in graph.out_neighbours(vertices[v]) {
+                let w = vertices.binary_search(&u).unwrap();
+                if depth[w] == usize::MAX {
+                    depth[w] = depth[v] + 1;
+                    order.push(w);
+                }
+                if depth[w] == depth[v] + 1 {
+                    paths[w] += paths[v];
+                    predecessors[w].push(v);
+                }
+            }
+            i += 1;
+        }
+
+        for &w in order.iter().rev() {
+            for &v in &predecessors[w] {
+                dependency[v] += paths[v] / paths[w] * (1.0 + dependency[w]);
+            }
+            if w != s {
+                centrality[w] += scale * dependency[w];
+            }
+        }
+        for &v in &order {
+            depth[v] = usize::MAX;
+            paths[v] = 0.0;
+            dependency[v] = 0.0;
+            predecessors[v].clear();
+        }
+    }
+    vertices.iter().cloned().zip(centrality).collect()
+}
@@ -39,12 +39,136 @@ use std::ptr;
 impl IntCol {
     #[inline]
     pub fn swap_range(&mut self, l: usize, a: usize, b: usize) {
+        debug_assert!(a + l <= self.crk.len() && b + l <= self.crk.len(), "swap_range: {} values from {} and {} overrun {} values", l, a, b, self.crk.len());
+        if a == b || l == 0 {
+            return;
+        }
+        // The RLE kernels occasionally swap ranges which overlap, in which case the swap is done
+        // element-wise from the front, as the kernels expect.
+        if a + l > b && b + l > a {
+            for i in 0..l {
+                self.crk.swap(a + i, b + i);
+                self.base_idx.swap(a + i, b + i);
+                self.run_lengths.swap(a + i, b + i);
+            }
+            return;
+        }
         unsafe {
             ptr::swap_nonoverlapping(&mut self.crk[a], &mut self.crk[b], l);
             ptr::swap_nonoverlapping(&mut self.base_idx[a], &mut self.base_idx[b], l);
             ptr::swap_nonoverlapping(&mut self.run_lengths[a], &mut self.run_lengths[b], l);
         }
     }
+
+    // Checks that base_idx is a permutation of the original column's indices, and that every
+    // cracked value is the original value it points at. If the column has been compacted, the
+    // ith cracked value stands for every base index in ofs[i] up to ofs[i + 1].
+    pub fn validate_alignment(&self) -> Result<(), String> {
+        let n = self.v.len();
+        if self.base_idx.len() != n {
+            return Err(format!("base_idx has {} entries but the column has {} values", self.base_idx.len(), n));
+        }
+        let mut seen = vec![false; n];
+        for (i, &b) in self.base_idx.iter().enumerate() {
+            if b >= n {
+                return Err(format!("base_idx[{}] = {} is out of bounds for {} values", i, b, n));
+            }
+            if seen[b] {
+                return Err(format!("base_idx[{}] = {} appears more than once", i, b));
+            }
+            seen[b] = true;
+        }
+
+        if self.ofs.is_empty() {
+            if self.crk.len() != n {
+                return Err(format!("crk has {} values but the column has {} values", self.crk.len(), n));
+            }
+            for i in 0..n {
+                if self.crk[i] != self.v[self.base_idx[i]] {
+                    return Err(format!("crk[{}] = {} but v[base_idx[{}] = {}] = {}", i, self.crk[i], i, self.base_idx[i], self.v[self.base_idx[i]]));
+                }
+            }
+        } else {
+            if self.ofs.len() != self.crk.len() {
+                return Err(format!("ofs has {} entries but crk has {} values", self.ofs.len(), self.crk.len()));
+            }
+            if self.ofs[0] != 0 {
+                return Err(format!("ofs[0] = {} but the first offset must be 0", self.ofs[0]));
+            }
+            for i in 0..self.ofs.len() {
+                let next_offset = if i + 1 == self.ofs.len() { n } else { self.ofs[i + 1] };
+                if next_offset <= self.ofs[i] || next_offset > n {
+                    return Err(format!("ofs[{}] = {} is not followed by a greater offset within {} values (next is {})", i, self.ofs[i], n, next_offset));
+                }
+                for j in self.ofs[i]..next_offset {
+                    if self.crk[i] != self.v[self.base_idx[j]] {
+                        return Err(format!("crk[{}] = {} covers base_idx[{}] = {} but v[{}] = {}", i, self.crk[i], j, self.base_idx[j], self.base_idx[j], self.v[self.base_idx[j]]));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Checks that every entry k -> p in the cracker index partitions crk, so that every value
+    // before p is less than k and every value from p onwards is at least k.
+    pub fn validate_crk_idx(&self) -> Result<(), String> {
+        let pairs = self.crk_idx.pairs();
+        let n = self.crk.len();
+        let mut p_prev = 0;
+        for (i, &(k, p)) in pairs.iter().enumerate() {
+            if p > n {
+                return Err(format!("crk_idx maps {} to {}, beyond the {} cracked values", k, p, n));
+            }
+            if p < p_prev {
+                return Err(format!("crk_idx maps {} to {}, before the position {} of a smaller key", k, p, p_prev));
+            }
+            if i == 0 {
+                if let Some(j) = (0..p).find(|&j| self.crk[j] >= k) {
+                    return Err(format!("crk_idx maps {} to {} but crk[{}] = {}", k, p, j, self.crk[j]));
+                }
+            }
+            // Values between this key's position and the next key's position lie in [k, next key).
+            let (p_next, k_next) = match pairs.get(i + 1) {
+                Some(&(k_next, p_next)) => (p_next.min(n), Some(k_next)),
+                None => (n, None),
+            };
+            for j in p..p_next {
+                if self.crk[j] < k || k_next.is_some_and(|k_next| self.crk[j] >= k_next) {
+                    return Err(format!("crk_idx maps {} to {} but crk[{}] = {}", k, p, j, self.crk[j]));
+                }
+            }
+            p_prev = p;
+        }
+        Ok(())
+    }
+
+    // Checks that the run lengths tile crk with runs of equal values, where the first and last
+    // entries of every run both hold the length of the run.
+    pub fn validate_run_lengths(&self) -> Result<(), String> {
+        let n = self.crk.len();
+        if self.run_lengths.len() != n {
+            return Err(format!("run_lengths has {} entries but crk has {} values", self.run_lengths.len(), n));
+        }
+        let mut i = 0;
+        while i < n {
+            let rl = self.run_lengths[i];
+            if rl == 0 {
+                return Err(format!("run_lengths[{}] = 0", i));
+            }
+            if i + rl > n {
+                return Err(format!("run at {} of length {} runs past the {} cracked values", i, rl, n));
+            }
+            if self.run_lengths[i + rl - 1] != rl {
+                return Err(format!("run at {} starts with length {} but run_lengths[{}] = {}", i, rl, i + rl - 1, self.run_lengths[i + rl - 1]));
+            }
+            if let Some(j) = (i..(i + rl)).find(|&j| self.crk[j] != self.crk[i]) {
+                return Err(format!("run at {} of length {} holds crk[{}] = {} but crk[{}] = {}", i, rl, i, self.crk[i], j, self.crk[j]));
+            }
+            i += rl;
+        }
+        Ok(())
+    }
 }
 
 impl Column for IntCol {
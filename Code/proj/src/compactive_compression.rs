@@ -178,6 +178,11 @@ impl CoCoTable {
     }
 
     pub fn decompress_values(&self, compressed_index: usize, col: &str) -> Vec<i64> {
+        self.get_values(self.crk_col.base_idx[self.piece(compressed_index)].iter(), col)
+    }
+
+    // Returns the positions in base_idx that the compressed index covers.
+    fn piece(&self, compressed_index: usize) -> Range<usize> {
         let offset = self.crk_col.ofs[compressed_index];
 
         let next_offset = if compressed_index >= self.crk_col.ofs.len() - 1 {
@@ -186,17 +191,7 @@ impl CoCoTable {
             self.crk_col.ofs[compressed_index + 1]
         };
 
-        let index_range = offset..next_offset;
-        let mut base_indices = Vec::with_capacity(next_offset - offset);
-        for i in index_range {
-            base_indices.push(self.crk_col.base_idx[i]);
-        }
-
-        let mut buf = Vec::new();
-        for i in base_indices {
-            buf.push(self.int_columns[&col.to_string()].v[i]);
-        }
-        buf
+        offset..next_offset
     }
 
     // Compact the cracker column if there is an opportunity to do so, given the recent crk_idx addition of V->I
@@ -251,8 +246,26 @@ impl CoCoTable {
         t
     }
 
-    // Returns the elements of T where the cracker columns's value is between LOW and HIGH, with inclusivity given by INC_L and INC_H.
+    // Returns the elements of T where the cracker columns's value equals X
     pub fn cracker_select_specific(&mut self, x: i64, col: &str) -> Vec<i64> {
+        let piece = self.crack_select_specific(x);
+        #[cfg(feature = "validate")]
+        self.validate().unwrap_or_else(|e| panic!("cracker_select_specific({}): {}", x, e));
+        self.get_values(self.crk_col.base_idx[piece].iter(), col)
+    }
+
+    // Returns the elements of each of COLS where the cracker column's value equals X. They are
+    // all read from the same piece, so the ith elements of each belong to the same tuple.
+    pub fn cracker_select_columns(&mut self, x: i64, cols: &[&str]) -> Vec<Vec<i64>> {
+        let piece = self.crack_select_specific(x);
+        #[cfg(feature = "validate")]
+        self.validate().unwrap_or_else(|e| panic!("cracker_select_columns({}): {}", x, e));
+        cols.iter().map(|col| self.get_values(self.crk_col.base_idx[piece.clone()].iter(), col)).collect()
+    }
+
+    // Cracks on X and returns the positions in base_idx of the tuples where the cracker column's
+    // value equals X.
+    fn crack_select_specific(&mut self, x: i64) -> Range<usize> {
         // PHASE 0: Setup
 
         if self.crk_col.ofs.is_empty() {
@@ -274,14 +287,14 @@ impl CoCoTable {
         while self.crk_col.crk[p_low] < x {
             p_low += 1;
             if p_low == compressed_count as usize {
-                return vec![];
+                return 0..0;
             }
         }
 
         // while p_high is pointing at an element satisfying c_high, move it backwards
         while self.crk_col.crk[p_high] > x {
             if p_high == 0 {
-                return vec![];
+                return 0..0;
             }
             p_high -= 1;
         }
@@ -290,15 +303,15 @@ impl CoCoTable {
         if p_low == p_high {
             if p_low == (compressed_count - 1) {
                 if self.crk_col.crk_idx.contains(self.crk_col.crk[p_low]) && self.crk_col.crk_idx.contains(self.crk_col.crk[p_low] + 1) {
-                    return self.decompress_values(p_low, col);
+                    return self.piece(p_low);
                 } else {
-                    return self.get_values(vec![self.crk_col.base_idx[self.crk_col.ofs[p_low]]].iter(), col);
+                    return self.crk_col.ofs[p_low]..(self.crk_col.ofs[p_low] + 1);
                 }
             } else {
                 if self.crk_col.ofs[p_low + 1] - self.crk_col.ofs[p_low] > 1 {
-                    return self.decompress_values(p_low, col);
+                    return self.piece(p_low);
                 } else {
-                    return self.get_values(vec![self.crk_col.base_idx[self.crk_col.ofs[p_low]]].iter(), col);
+                    return self.crk_col.ofs[p_low]..(self.crk_col.ofs[p_low] + 1);
                 }
             }
         }
@@ -331,6 +344,8 @@ impl CoCoTable {
 
         // PHASE 3: Compression
 
+        let selected = p_low <= p_high;
+
         if p_itr <= self.crk_col.crk.len() {
             self.crk_col.crk_idx.insert(x + 1, p_itr);
             self.compact(x + 1, p_itr);
@@ -341,11 +356,34 @@ impl CoCoTable {
 
         // PHASE 4: Decompression
 
-        if p_low <= p_high {
-            self.decompress_values(p_low, col)
+        // Compacting the piece before x moves x's piece, so find where it is now.
+        if selected {
+            let p_low = self.crk_col.crk_idx.get(x).unwrap_or(p_low);
+            self.piece(p_low)
         } else {
-            vec![]
+            0..0
+        }
+    }
+
+    // Checks the invariants of the table and its cracker column, returning a description of the
+    // first violation found.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, c) in &self.int_columns {
+            if c.v.len() != self.count {
+                return Err(format!("column {} has {} values but the table has {} tuples", name, c.v.len(), self.count));
+            }
         }
+        if self.crk_col.v.is_empty() {
+            // No cracker column has been set.
+            return Ok(());
+        }
+        match self.int_columns.get(&self.crk_col_name) {
+            Some(c) if c.v == self.crk_col.v => {},
+            Some(_) => return Err(format!("crk_col.v differs from column {}", self.crk_col_name)),
+            None => return Err(format!("crk_col_name {} is not a column", self.crk_col_name)),
+        }
+        self.crk_col.validate_alignment()?;
+        self.crk_col.validate_crk_idx()
     }
 
     // Counts the places where a given column equals a given value
@@ -362,3 +400,13 @@ pub fn from_adjacency_vectors(src_node: Vec<i64>, dst_node: Vec<i64>, crk: &str)
     adjacency_list.set_crk_col(crk);
     adjacency_list
 }
+
+// Returns an adjacency list with a weight for each edge, in column "weight". Cracking keeps it
+// aligned with src and dst through base_idx.
+pub fn from_weighted_adjacency_vectors(src_node: Vec<i64>, dst_node: Vec<i64>, weight: Vec<i64>, crk: &str) -> CoCoTable {
+    let mut adjacency_list = CoCoTable::new();
+    adjacency_list.new_columns(vec!["src", "dst", "weight"]);
+    adjacency_list.insert(&mut map!{"src" => src_node, "dst" => dst_node, "weight" => weight});
+    adjacency_list.set_crk_col(crk);
+    adjacency_list
+}
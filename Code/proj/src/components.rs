@@ -0,0 +1,167 @@
+// Connected components
+// Weakly connected components by union-find over every edge, and strongly connected components by
+// Tarjan's algorithm, over any Graph. Components are numbered from 0 in the order of their
+// smallest vertex.
+
+use std::collections::{BTreeMap, HashMap};
+
+use graph::Graph;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Components {
+    component: HashMap<i64, usize>,
+    sizes: Vec<usize>,
+}
+
+impl Components {
+    // Numbers the components of VERTICES, given in ascending order, from a representative of
+    // each vertex's component.
+    fn from_representatives(vertices: &[i64], representative: &[usize]) -> Components {
+        let mut numbers: HashMap<usize, usize> = HashMap::new();
+        let mut components = Components { component: HashMap::new(), sizes: Vec::new() };
+        for (p, &v) in vertices.iter().enumerate() {
+            let next = numbers.len();
+            let c = *numbers.entry(representative[p]).or_insert(next);
+            if c == components.sizes.len() {
+                components.sizes.push(0);
+            }
+            components.sizes[c] += 1;
+            components.component.insert(v, c);
+        }
+        components
+    }
+
+    pub fn component(&self, v: i64) -> Option<usize> {
+        self.component.get(&v).cloned()
+    }
+
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    // The size of each component, by number.
+    pub fn sizes(&self) -> &[usize] {
+        &self.sizes
+    }
+
+    pub fn largest(&self) -> usize {
+        self.sizes.iter().cloned().max().unwrap_or(0)
+    }
+
+    // The number of components of each size.
+    pub fn size_distribution(&self) -> BTreeMap<usize, usize> {
+        let mut distribution = BTreeMap::new();
+        for &s in &self.sizes {
+            *distribution.entry(s).or_insert(0) += 1;
+        }
+        distribution
+    }
+
+    // The vertices of component C in ascending order.
+    pub fn members(&self, c: usize) -> Vec<i64> {
+        let mut members: Vec<i64> = self.component.iter().filter(|e| *e.1 == c).map(|e| *e.0).collect();
+        members.sort();
+        members
+    }
+}
+
+// Finds the root of P's set, halving the path to it.
+fn find(parent: &mut [usize], mut p: usize) -> usize {
+    while parent[p] != p {
+        parent[p] = parent[parent[p]];
+        p = parent[p];
+    }
+    p
+}
+
+pub fn weakly_connected_components<G: Graph + ?Sized>(graph: &mut G) -> Components {
+    let vertices = graph.vertices().to_vec();
+    let mut parent: Vec<usize> = (0..vertices.len()).collect();
+    let mut size = vec![1; vertices.len()];
+    for (p, &v) in vertices.iter().enumerate() {
+        for u in graph.out_neighbours(v) {
+            let (a, b) = (find(&mut parent, p), find(&mut parent, vertices.binary_search(&u).unwrap()));
+            if a == b {
+                continue;
+            }
+            // Union by size.
+            let (small, large) = if size[a] < size[b] { (a, b) } else { (b, a) };
+            parent[small] = large;
+            size[large] += size[small];
+        }
+    }
+    let roots: Vec<usize> = (0..vertices.len()).map(|p| find(&mut parent, p)).collect();
+    Components::from_representatives(&vertices, &roots)
+}
+
+// Tarjan's algorithm, with an explicit stack of the vertices being explored so that long paths
+// don't overflow the call stack.
+pub fn strongly_connected_components<G: Graph + ?Sized>(graph: &mut G) -> Components {
+    let vertices = graph.vertices().to_vec();
+    let n = vertices.len();
+    let mut tarjan = Tarjan { index: vec![UNVISITED; n], low_link: vec![0; n], on_stack: vec![false; n], stack: Vec::new(), next_index: 0 };
+    let mut root = vec![0; n];
+
+    for s in 0..n {
+        if tarjan.index[s] != UNVISITED {
+            continue;
+        }
+        // Each vertex being explored, with its out-neighbours and how many of them it has explored.
+        let mut exploring: Vec<(usize, Vec<usize>, usize)> = Vec::new();
+        tarjan.visit(s);
+        exploring.push((s, positions(graph, &vertices, s), 0));
+        while let Some((p, neighbours, i)) = exploring.pop() {
+            if i < neighbours.len() {
+                let q = neighbours[i];
+                exploring.push((p, neighbours, i + 1));
+                if tarjan.index[q] == UNVISITED {
+                    tarjan.visit(q);
+                    exploring.push((q, positions(graph, &vertices, q), 0));
+                } else if tarjan.on_stack[q] {
+                    tarjan.low_link[p] = tarjan.low_link[p].min(tarjan.index[q]);
+                }
+                continue;
+            }
+            // P is explored: pass its low link to its parent, and pop its component if it's the root.
+            if let Some(parent) = exploring.last() {
+                tarjan.low_link[parent.0] = tarjan.low_link[parent.0].min(tarjan.low_link[p]);
+            }
+            if tarjan.low_link[p] == tarjan.index[p] {
+                loop {
+                    let q = tarjan.stack.pop().unwrap();
+                    tarjan.on_stack[q] = false;
+                    root[q] = p;
+                    if q == p {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    Components::from_representatives(&vertices, &root)
+}
+
+const UNVISITED: usize = usize::MAX;
+
+struct Tarjan {
+    index: Vec<usize>,
+    low_link: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    next_index: usize,
+}
+
+impl Tarjan {
+    fn visit(&mut self, p: usize) {
+        self.index[p] = self.next_index;
+        self.low_link[p] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(p);
+        self.on_stack[p] = true;
+    }
+}
+
+// The positions in VERTICES of the out-neighbours of the vertex at position P.
+fn positions<G: Graph + ?Sized>(graph: &mut G, vertices: &[i64], p: usize) -> Vec<usize> {
+    graph.out_neighbours(vertices[p]).iter().map(|u| vertices.binary_search(u).unwrap()).collect()
+}
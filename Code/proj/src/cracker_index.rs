@@ -350,6 +350,17 @@ pub fn subtract_where_greater_than(threshold: i64, amount: usize, root: &mut Box
     }
 }
 
+// Appends every (key, data) pair in the tree to PAIRS in ascending key order.
+pub fn collect_pairs(root: &AVLNode, pairs: &mut Vec<(i64, usize)>) {
+    if let Some(ref left) = root.left {
+        collect_pairs(left, pairs);
+    }
+    pairs.push((root.key, root.data));
+    if let Some(ref right) = root.right {
+        collect_pairs(right, pairs);
+    }
+}
+
 pub fn print_nodes(root: &Box<AVLNode>) {
     print!("{} -> {} | ", root.key, root.data);
     root.left.as_ref().map(|t| print_nodes(&t));
@@ -437,6 +448,15 @@ impl AVLCrackerIndex {
         }
     }
 
+    // Returns every (key, position) pair in the index, in ascending key order.
+    pub fn pairs(&self) -> Vec<(i64, usize)> {
+        let mut pairs = Vec::new();
+        if let Some(ref root) = self.root {
+            collect_pairs(root, &mut pairs);
+        }
+        pairs
+    }
+
     pub fn print(&self) {
         match self.root {
             Some(ref root) => {
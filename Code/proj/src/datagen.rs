@@ -1,36 +1,33 @@
-use rand::Rng; // HMM
-use rand;
+use rand::{Rng, SeedableRng, StdRng};
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io;
+use std::io::{BufWriter, Write};
+use std::iter;
 
 // Finds the directed density of a graph with n nodes and e edges. Returned as a float.
 pub fn graph_density(n: i64, e: usize) -> f64 {
     (e as f64) / ((n * (n - 1)) as f64)
 }
 
+// Every generator takes a seed, and generates the same graph whenever it is given the same seed.
+pub fn seeded_rng(seed: usize) -> StdRng {
+    SeedableRng::from_seed(&[seed][..])
+}
+
 // Deals out the numbers from 0 to n-1 inclusive in a random order as usizes.
-fn deal(n: usize) -> Vec<usize> {
-    // Put 1 - n in a bag.
-    let mut dealing: Vec<usize> = Vec::with_capacity(n as usize);
-    for i in 0..n {
-        dealing.push(i);
-    }
-    let mut dealt: Vec<usize> = Vec::with_capacity(n as usize);
-    let mut rng = rand::thread_rng();
-    let mut chosen = 0;
-    while dealing.len() > 1 {
-        let random = rng.gen_range(0, n - chosen);
-        dealt.push(dealing[random]);
-        dealing.remove(random);
-        chosen = chosen + 1;
-    }
-    dealt.push(dealing[0]);
+fn deal(n: usize, rng: &mut StdRng) -> Vec<usize> {
+    let mut dealt: Vec<usize> = (0..n).collect();
+    rng.shuffle(&mut dealt);
     dealt
 }
 
-fn pairwise_shuffle(src: Vec<i64>, dst: Vec<i64>) -> (Vec<i64>, Vec<i64>) {
+fn pairwise_shuffle(src: Vec<i64>, dst: Vec<i64>, rng: &mut StdRng) -> (Vec<i64>, Vec<i64>) {
     let n = src.len();
     let mut src_shuffled = Vec::with_capacity(n);
     let mut dst_shuffled = Vec::with_capacity(n);
-    for i in deal(n) {
+    for i in deal(n, rng) {
         src_shuffled.push(src[i]);
         dst_shuffled.push(dst[i]);
     }
@@ -38,24 +35,22 @@ fn pairwise_shuffle(src: Vec<i64>, dst: Vec<i64>) -> (Vec<i64>, Vec<i64>) {
 }
 
 // Returns a bidirectionally connected tree for n nodes, which are numbered 1 to n inclusive.
-pub fn randomly_connected_tree(n: i64) -> (Vec<i64>, Vec<i64>) {
-    let mut add_order: Vec<i64> = deal(n as usize).iter().map(|x| 1 + *x as i64).collect();
-
-    let node_1 = *rand::thread_rng().choose(&add_order).unwrap();
-    let mut node_2 = *rand::thread_rng().choose(&add_order).unwrap();
-    while node_2 == node_1 {
-        node_2 = *rand::thread_rng().choose(&add_order).unwrap();
+pub fn randomly_connected_tree(n: i64, seed: usize) -> (Vec<i64>, Vec<i64>) {
+    if n < 2 {
+        panic!("randomly_connected_tree: Can't connect {} nodes", n);
     }
+    let mut rng = seeded_rng(seed);
+    let add_order: Vec<i64> = deal(n as usize, &mut rng).iter().map(|x| 1 + *x as i64).collect();
 
-    let index_1 = add_order.iter().position(|node| *node == node_1).unwrap();
-    add_order.remove(index_1);
-    let index_2 = add_order.iter().position(|node| *node == node_2).unwrap();
-    add_order.remove(index_2);
-
-    let mut src_col = vec![node_1, node_2];
-    let mut dst_col = vec![node_2, node_1];
-    for src in add_order {
-        let dst = *rand::thread_rng().choose(&src_col).unwrap();
+    // Each node is connected to one chosen from src_col, which holds every node already in the tree
+    // once for each of its edges.
+    let e = 2 * (n as usize - 1);
+    let mut src_col = Vec::with_capacity(e);
+    let mut dst_col = Vec::with_capacity(e);
+    src_col.extend_from_slice(&[add_order[0], add_order[1]]);
+    dst_col.extend_from_slice(&[add_order[1], add_order[0]]);
+    for &src in &add_order[2..] {
+        let dst = *rng.choose(&src_col).unwrap();
 
         src_col.push(src);
         dst_col.push(dst);
@@ -63,11 +58,11 @@ pub fn randomly_connected_tree(n: i64) -> (Vec<i64>, Vec<i64>) {
         src_col.push(dst);
         dst_col.push(src);
     }
-    pairwise_shuffle(src_col, dst_col)
+    pairwise_shuffle(src_col, dst_col, &mut rng)
 }
 
-pub fn randomly_connected_graph(n: i64, d: f64) -> (Vec<i64>, Vec<i64>) {
-    let mut rng = rand::thread_rng();
+pub fn randomly_connected_graph(n: i64, d: f64, seed: usize) -> (Vec<i64>, Vec<i64>) {
+    let mut rng = seeded_rng(seed);
 
     // Start with two random edges from a single source
     let src_1 = rng.gen_range(0, n);
@@ -105,5 +100,438 @@ pub fn randomly_connected_graph(n: i64, d: f64) -> (Vec<i64>, Vec<i64>) {
         dst.push(dst_out);
     }
 
-    pairwise_shuffle(src, dst)
-}
\ No newline at end of file
+    pairwise_shuffle(src, dst, &mut rng)
+}
+
+// Picks a start node for a traversal of a graph whose nodes are numbered 1 to n inclusive.
+pub fn random_start_node(n: i64, seed: usize) -> i64 {
+    seeded_rng(seed).gen_range(1, n)
+}
+
+// Random graph models
+// Each generator numbers its nodes 1 to n inclusive. Models of undirected graphs give each edge
+// in both directions, as randomly_connected_tree does.
+
+// The graphs which the benchmarks can be run over.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Model {
+    // A randomly connected tree of n nodes.
+    Tree(i64),
+    // R-MAT with 2^scale nodes, edge_factor edges per node and quadrant probabilities a, b and c.
+    RMat { scale: u32, edge_factor: usize, a: f64, b: f64, c: f64 },
+    // Barabási–Albert preferential attachment of n nodes, each attaching to m earlier nodes.
+    BarabasiAlbert { n: i64, m: usize },
+    // Erdős–Rényi G(n, p).
+    Gnp { n: i64, p: f64 },
+    // Erdős–Rényi G(n, m).
+    Gnm { n: i64, m: usize },
+    // A rows by cols grid, with edges between horizontally and vertically adjacent nodes.
+    Grid { rows: i64, cols: i64 },
+    // A stochastic block model with the given block sizes, where nodes in blocks i and j are
+    // connected with probability p[i][j].
+    Sbm { block_sizes: Vec<usize>, p: Vec<Vec<f64>> },
+}
+
+impl Model {
+    pub fn nodes(&self) -> i64 {
+        match *self {
+            Model::Tree(n) => n,
+            Model::RMat { scale, .. } => 1 << scale,
+            Model::BarabasiAlbert { n, .. } => n,
+            Model::Gnp { n, .. } => n,
+            Model::Gnm { n, .. } => n,
+            Model::Grid { rows, cols } => rows * cols,
+            Model::Sbm { ref block_sizes, .. } => block_sizes.iter().sum::<usize>() as i64,
+        }
+    }
+}
+
+pub fn generate(model: &Model, seed: usize) -> (Vec<i64>, Vec<i64>) {
+    match *model {
+        Model::Tree(n) => randomly_connected_tree(n, seed),
+        Model::RMat { scale, edge_factor, a, b, c } => rmat(scale, edge_factor, a, b, c, seed),
+        Model::BarabasiAlbert { n, m } => barabasi_albert(n, m, seed),
+        Model::Gnp { n, p } => erdos_renyi_gnp(n, p, seed),
+        Model::Gnm { n, m } => erdos_renyi_gnm(n, m, seed),
+        Model::Grid { rows, cols } => grid_2d(rows, cols, seed),
+        Model::Sbm { ref block_sizes, ref p } => stochastic_block_model(block_sizes, p, seed),
+    }
+}
+
+fn push_undirected(src: &mut Vec<i64>, dst: &mut Vec<i64>, u: i64, v: i64) {
+    src.push(u);
+    dst.push(v);
+    src.push(v);
+    dst.push(u);
+}
+
+// Picks each of the indices 0 to total-1 independently with probability p. Unpicked indices are
+// skipped over in geometrically distributed jumps, so the time taken is proportional to the number
+// of indices picked rather than to total.
+struct BernoulliIndices<R> {
+    total: usize,
+    log_q: f64,
+    next: usize,
+    rng: R,
+}
+
+fn bernoulli_indices<R: Rng>(total: usize, p: f64, rng: R) -> BernoulliIndices<R> {
+    if !(0.0..=1.0).contains(&p) {
+        panic!("bernoulli_indices: Probability {} is not in [0, 1]", p);
+    }
+    // With p = 0 no index is ever picked, and with p = 1 every skip is zero.
+    let next = if p == 0.0 { total } else { 0 };
+    BernoulliIndices { total, log_q: (1.0 - p).ln(), next, rng }
+}
+
+impl<R: Rng> Iterator for BernoulliIndices<R> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.next >= self.total {
+            return None;
+        }
+        let skip = ((1.0 - self.rng.gen::<f64>()).ln() / self.log_q).floor() as usize;
+        let k = self.next.saturating_add(skip);
+        if k >= self.total {
+            self.next = self.total;
+            return None;
+        }
+        self.next = k + 1;
+        Some(k)
+    }
+}
+
+// Maps k to the kth pair (i, j) with 0 <= j < i, in the order (1, 0), (2, 0), (2, 1), (3, 0), ...
+fn triangle_pair(k: usize) -> (usize, usize) {
+    let mut i = ((1.0 + (1.0 + 8.0 * k as f64).sqrt()) / 2.0) as usize;
+    while i * (i - 1) / 2 > k {
+        i -= 1;
+    }
+    while (i + 1) * i / 2 <= k {
+        i += 1;
+    }
+    (i, k - i * (i - 1) / 2)
+}
+
+// Returns a directed R-MAT graph (Chakrabarti et al.) of 2^scale nodes and edge_factor * 2^scale
+// edges. Each edge is placed by recursively choosing a quadrant of the adjacency matrix with
+// probabilities a, b, c and 1 - a - b - c. Self loops are redrawn but duplicate edges are kept, so
+// b and c mustn't both be 0.
+// Node numbers are randomly permuted so that the high degree nodes aren't all numbered low.
+pub fn rmat(scale: u32, edge_factor: usize, a: f64, b: f64, c: f64, seed: usize) -> (Vec<i64>, Vec<i64>) {
+    if a < 0.0 || b < 0.0 || c < 0.0 || a + b + c > 1.0 {
+        panic!("rmat: Quadrant probabilities {}, {}, {} don't form a distribution", a, b, c);
+    }
+    // Otherwise every edge is on the diagonal, and self loops are redrawn forever.
+    if b + c == 0.0 {
+        panic!("rmat: Quadrant probabilities b and c are both 0, so every edge is a self loop");
+    }
+    let n: usize = 1 << scale;
+    if n < 2 {
+        panic!("rmat: Need at least two nodes, but scale is {}", scale);
+    }
+    let mut rng = seeded_rng(seed);
+    let labels: Vec<i64> = deal(n, &mut rng).iter().map(|x| 1 + *x as i64).collect();
+
+    let e = edge_factor * n;
+    let mut src = Vec::with_capacity(e);
+    let mut dst = Vec::with_capacity(e);
+    while src.len() < e {
+        let mut u = 0;
+        let mut v = 0;
+        for _ in 0..scale {
+            let r = rng.gen::<f64>();
+            let (i, j) = if r < a { (0, 0) } else if r < a + b { (0, 1) } else if r < a + b + c { (1, 0) } else { (1, 1) };
+            u = (u << 1) | i;
+            v = (v << 1) | j;
+        }
+        if u != v {
+            src.push(labels[u]);
+            dst.push(labels[v]);
+        }
+    }
+    (src, dst)
+}
+
+// Returns a Barabási–Albert preferential attachment graph. It starts as a clique of m + 1 nodes,
+// then each further node is connected to m distinct earlier nodes, chosen with probability
+// proportional to their degree.
+pub fn barabasi_albert(n: i64, m: usize, seed: usize) -> (Vec<i64>, Vec<i64>) {
+    if m == 0 || (n as usize) < m + 1 {
+        panic!("barabasi_albert: Can't attach {} nodes to {} others", n, m);
+    }
+    let mut rng = seeded_rng(seed);
+    let mut src = Vec::new();
+    let mut dst = Vec::new();
+    for u in 1..(m as i64 + 2) {
+        for v in 1..u {
+            push_undirected(&mut src, &mut dst, u, v);
+        }
+    }
+    // Every edge appears in both directions, so a node appears in src once for each unit of its
+    // degree, and a uniformly random element of src is chosen proportionally to degree.
+    for u in (m as i64 + 2)..(n + 1) {
+        let mut targets: Vec<i64> = Vec::with_capacity(m);
+        while targets.len() < m {
+            let v = src[rng.gen_range(0, src.len())];
+            if !targets.contains(&v) {
+                targets.push(v);
+            }
+        }
+        for v in targets {
+            push_undirected(&mut src, &mut dst, u, v);
+        }
+    }
+    pairwise_shuffle(src, dst, &mut rng)
+}
+
+// Returns an undirected Erdős–Rényi graph on n nodes where each edge exists with probability p.
+pub fn erdos_renyi_gnp(n: i64, p: f64, seed: usize) -> (Vec<i64>, Vec<i64>) {
+    let mut rng = seeded_rng(seed);
+    let pairs = (n as usize) * (n as usize).saturating_sub(1) / 2;
+    let mut src = Vec::new();
+    let mut dst = Vec::new();
+    for k in bernoulli_indices(pairs, p, &mut rng) {
+        let (i, j) = triangle_pair(k);
+        push_undirected(&mut src, &mut dst, 1 + i as i64, 1 + j as i64);
+    }
+    pairwise_shuffle(src, dst, &mut rng)
+}
+
+// Returns an undirected Erdős–Rényi graph on n nodes with m edges, chosen uniformly at random
+// from all such graphs.
+pub fn erdos_renyi_gnm(n: i64, m: usize, seed: usize) -> (Vec<i64>, Vec<i64>) {
+    let pairs = (n as usize) * (n as usize).saturating_sub(1) / 2;
+    if m > pairs {
+        panic!("erdos_renyi_gnm: {} nodes can't have {} edges", n, m);
+    }
+    let mut rng = seeded_rng(seed);
+    // Sample whichever of the edges or the non-edges is smaller.
+    let complement = m > pairs / 2;
+    let wanted = if complement { pairs - m } else { m };
+    let mut chosen = HashSet::with_capacity(wanted);
+    while chosen.len() < wanted {
+        chosen.insert(rng.gen_range(0, pairs));
+    }
+    let mut src = Vec::with_capacity(2 * m);
+    let mut dst = Vec::with_capacity(2 * m);
+    let mut add = |k: usize| {
+        let (i, j) = triangle_pair(k);
+        push_undirected(&mut src, &mut dst, 1 + i as i64, 1 + j as i64);
+    };
+    if complement {
+        (0..pairs).filter(|k| !chosen.contains(k)).for_each(&mut add);
+    } else {
+        let mut ks: Vec<usize> = chosen.into_iter().collect();
+        ks.sort();
+        ks.into_iter().for_each(&mut add);
+    }
+    pairwise_shuffle(src, dst, &mut rng)
+}
+
+// Returns an undirected rows by cols grid, numbered row by row. The graph itself isn't random;
+// the seed only shuffles the order of the edges.
+pub fn grid_2d(rows: i64, cols: i64, seed: usize) -> (Vec<i64>, Vec<i64>) {
+    let mut rng = seeded_rng(seed);
+    let mut src = Vec::new();
+    let mut dst = Vec::new();
+    for r in 0..rows {
+        for c in 0..cols {
+            let u = 1 + r * cols + c;
+            if c + 1 < cols {
+                push_undirected(&mut src, &mut dst, u, u + 1);
+            }
+            if r + 1 < rows {
+                push_undirected(&mut src, &mut dst, u, u + cols);
+            }
+        }
+    }
+    pairwise_shuffle(src, dst, &mut rng)
+}
+
+// Returns an undirected stochastic block model graph. The nodes are split into consecutively
+// numbered blocks of the given sizes, and a node in block i is connected to a node in block j with
+// probability p[i][j], which must be symmetric.
+pub fn stochastic_block_model(block_sizes: &[usize], p: &[Vec<f64>], seed: usize) -> (Vec<i64>, Vec<i64>) {
+    let blocks = block_sizes.len();
+    if p.len() != blocks || p.iter().any(|row| row.len() != blocks) {
+        panic!("stochastic_block_model: Need a {} by {} matrix of probabilities", blocks, blocks);
+    }
+    for (i, row) in p.iter().enumerate() {
+        for (j, pij) in row.iter().enumerate().take(i) {
+            if *pij != p[j][i] {
+                panic!("stochastic_block_model: Probabilities p[{}][{}] and p[{}][{}] differ", i, j, j, i);
+            }
+        }
+    }
+    let mut rng = seeded_rng(seed);
+    let mut first = Vec::with_capacity(blocks);
+    let mut next = 1;
+    for size in block_sizes {
+        first.push(next);
+        next += *size as i64;
+    }
+
+    let mut src = Vec::new();
+    let mut dst = Vec::new();
+    for i in 0..blocks {
+        let pairs = block_sizes[i] * block_sizes[i].saturating_sub(1) / 2;
+        for k in bernoulli_indices(pairs, p[i][i], &mut rng) {
+            let (u, v) = triangle_pair(k);
+            push_undirected(&mut src, &mut dst, first[i] + u as i64, first[i] + v as i64);
+        }
+        for j in (i + 1)..blocks {
+            for k in bernoulli_indices(block_sizes[i] * block_sizes[j], p[i][j], &mut rng) {
+                let (u, v) = (k / block_sizes[j], k % block_sizes[j]);
+                push_undirected(&mut src, &mut dst, first[i] + u as i64, first[j] + v as i64);
+            }
+        }
+    }
+    pairwise_shuffle(src, dst, &mut rng)
+}
+
+// Streaming generation
+// These generators produce their edges one at a time, in time linear in the number of edges and
+// in constant memory, for graphs too large to build in memory before they're written out. Since
+// the edges can't be shuffled as a whole, node numbers are randomly permuted instead.
+
+// A random bijection on 0 to n-1 which is computed rather than stored. It's a four round Feistel
+// network over the smallest even number of bits which covers n, and values which fall outside of
+// 0 to n-1 are put through the network again until they don't.
+#[derive(Clone, Copy, Debug)]
+pub struct Permutation {
+    n: u64,
+    half_bits: u32,
+    key: u64,
+}
+
+impl Permutation {
+    pub fn new(n: u64, seed: usize) -> Permutation {
+        let mut half_bits = 1;
+        while 1u64 << (2 * half_bits) < n {
+            half_bits += 1;
+        }
+        Permutation { n, half_bits, key: mix(seed as u64) }
+    }
+
+    pub fn apply(&self, x: u64) -> u64 {
+        if x >= self.n {
+            panic!("apply: {} is outside of the permuted range 0..{}", x, self.n);
+        }
+        let mut y = self.feistel(x);
+        while y >= self.n {
+            y = self.feistel(y);
+        }
+        y
+    }
+
+    fn feistel(&self, x: u64) -> u64 {
+        let mask = (1u64 << self.half_bits) - 1;
+        let mut l = x >> self.half_bits;
+        let mut r = x & mask;
+        for round in 0..4 {
+            let f = mix(self.key ^ (round << 32) ^ r) & mask;
+            let next_r = l ^ f;
+            l = r;
+            r = next_r;
+        }
+        (l << self.half_bits) | r
+    }
+}
+
+// The splitmix64 finaliser.
+fn mix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// Streams a bidirectionally connected tree for n nodes, numbered 1 to n inclusive, of the same
+// model as randomly_connected_tree: each node joins by a node drawn uniformly from the column of
+// the endpoints of the edges so far, so in proportion to degree. The column isn't stored. Counting
+// nodes from 0 as they join, entry 2(j - 1) of it is node j, for j of at least 2, and entry
+// 2(j - 1) + 1 is the node j joined by, which is found by hashing j to draw it again.
+pub fn stream_tree(n: i64, seed: usize) -> impl Iterator<Item = (i64, i64)> {
+    let labels = Permutation::new(n as u64, seed);
+    let key = mix(!(seed as u64));
+    // The entry node k joins the tree by, which is one of the 2(k - 1) before it.
+    let draw = move |k: u64| mix(key ^ k) % (2 * (k - 1));
+    (1..n.max(1) as u64).flat_map(move |k| {
+        let mut i = if k == 1 { 0 } else { draw(k) };
+        while i >= 2 && i % 2 == 1 {
+            i = draw(i / 2 + 1);
+        }
+        let joined_by = if i < 2 { i } else { i / 2 + 1 };
+        let u = 1 + labels.apply(k) as i64;
+        let v = 1 + labels.apply(joined_by) as i64;
+        iter::once((u, v)).chain(iter::once((v, u)))
+    })
+}
+
+// Streams a directed R-MAT graph, as described for rmat.
+pub fn stream_rmat(scale: u32, edge_factor: usize, a: f64, b: f64, c: f64, seed: usize) -> impl Iterator<Item = (i64, i64)> {
+    if a < 0.0 || b < 0.0 || c < 0.0 || a + b + c > 1.0 {
+        panic!("stream_rmat: Quadrant probabilities {}, {}, {} don't form a distribution", a, b, c);
+    }
+    // Otherwise every edge is on the diagonal, and self loops are redrawn forever.
+    if b + c == 0.0 {
+        panic!("stream_rmat: Quadrant probabilities b and c are both 0, so every edge is a self loop");
+    }
+    let n: u64 = 1 << scale;
+    if n < 2 {
+        panic!("stream_rmat: Need at least two nodes, but scale is {}", scale);
+    }
+    let mut rng = seeded_rng(seed);
+    let labels = Permutation::new(n, seed);
+    (0..edge_factor as u64 * n).map(move |_| loop {
+        let mut u = 0;
+        let mut v = 0;
+        for _ in 0..scale {
+            let r = rng.gen::<f64>();
+            let (i, j) = if r < a { (0, 0) } else if r < a + b { (0, 1) } else if r < a + b + c { (1, 0) } else { (1, 1) };
+            u = (u << 1) | i;
+            v = (v << 1) | j;
+        }
+        if u != v {
+            return (1 + labels.apply(u) as i64, 1 + labels.apply(v) as i64);
+        }
+    })
+}
+
+// Streams an undirected Erdős–Rényi graph on n nodes where each edge exists with probability p.
+pub fn stream_gnp(n: i64, p: f64, seed: usize) -> impl Iterator<Item = (i64, i64)> {
+    let pairs = (n as usize) * (n as usize).saturating_sub(1) / 2;
+    let labels = Permutation::new(n.max(1) as u64, seed);
+    bernoulli_indices(pairs, p, seeded_rng(seed)).flat_map(move |k| {
+        let (i, j) = triangle_pair(k);
+        let u = 1 + labels.apply(i as u64) as i64;
+        let v = 1 + labels.apply(j as u64) as i64;
+        iter::once((u, v)).chain(iter::once((v, u)))
+    })
+}
+
+// Streams an undirected rows by cols grid, numbered row by row.
+pub fn stream_grid(rows: i64, cols: i64) -> impl Iterator<Item = (i64, i64)> {
+    (0..rows * cols).flat_map(move |k| {
+        let (r, c) = (k / cols, k % cols);
+        let u = 1 + k;
+        let right = if c + 1 < cols { Some(u + 1) } else { None };
+        let down = if r + 1 < rows { Some(u + cols) } else { None };
+        right.into_iter().chain(down).flat_map(move |v| iter::once((u, v)).chain(iter::once((v, u))))
+    })
+}
+
+// Writes streamed edges to the given file as a whitespace separated edge list, one edge per line,
+// returning the number of edges written.
+pub fn write_edge_list<I>(edges: I, path: &str) -> io::Result<usize> where I: Iterator<Item = (i64, i64)> {
+    let mut out = BufWriter::new(File::create(path)?);
+    let mut e = 0;
+    for (src, dst) in edges {
+        writeln!(out, "{}\t{}", src, dst)?;
+        e += 1;
+    }
+    out.flush()?;
+    Ok(e)
+}
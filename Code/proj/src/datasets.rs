@@ -0,0 +1,122 @@
+// LDBC dataset registry
+// Finds the directories holding the LDBC social network at each scale factor. A scale factor's
+// directory is looked up, in order:
+//   1. in the config file, as a line "sf<scale factor> = <directory>";
+//   2. as social_network_sf<scale factor> under the LDBC_DATA_HOME environment variable;
+//   3. as social_network_sf<scale factor> under the config file's "data_home = <directory>".
+// The config file is named by the LDBC_CONFIG environment variable, or is ldbc.conf in the working
+// directory if that exists. Blank lines and lines starting with '#' are ignored.
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DATA_HOME_VAR: &str = "LDBC_DATA_HOME";
+pub const CONFIG_VAR: &str = "LDBC_CONFIG";
+pub const DEFAULT_CONFIG: &str = "ldbc.conf";
+
+pub const NODES_FILE: &str = "person_0_0.csv";
+pub const EDGES_FILE: &str = "person_knows_person_0_0.csv";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Registry {
+    pub data_home: Option<PathBuf>,
+    pub config_data_home: Option<PathBuf>,
+    pub scale_factors: HashMap<String, PathBuf>,
+}
+
+// A social network directory which has been checked to hold the person and knows files.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dataset {
+    pub dir: PathBuf,
+}
+
+impl Registry {
+    // Reads the registry from the environment and the config file, if there is one.
+    pub fn from_env() -> Result<Registry, String> {
+        let config = match env::var(CONFIG_VAR) {
+            Ok(path) => Some(PathBuf::from(path)),
+            Err(_) if Path::new(DEFAULT_CONFIG).exists() => Some(PathBuf::from(DEFAULT_CONFIG)),
+            Err(_) => None,
+        };
+        let mut registry = match config {
+            Some(path) => {
+                let text = fs::read_to_string(&path).map_err(|e| format!("Can't read LDBC config {}: {}", path.display(), e))?;
+                Registry::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))?
+            },
+            None => Registry::default(),
+        };
+        registry.data_home = env::var(DATA_HOME_VAR).ok().map(PathBuf::from);
+        Ok(registry)
+    }
+
+    pub fn parse(config: &str) -> Result<Registry, String> {
+        let mut registry = Registry::default();
+        for (i, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = match line.find('=') {
+                Some(eq) => (line[..eq].trim(), line[eq + 1..].trim()),
+                None => return Err(format!("line {}: Expected 'key = value'", i + 1)),
+            };
+            if value.is_empty() {
+                return Err(format!("line {}: No directory given for {}", i + 1, key));
+            }
+            if key == "data_home" {
+                registry.config_data_home = Some(PathBuf::from(value));
+            } else if key.starts_with("sf") && key.len() > 2 {
+                registry.scale_factors.insert(key[2..].to_string(), PathBuf::from(value));
+            } else {
+                return Err(format!("line {}: Unknown key '{}'", i + 1, key));
+            }
+        }
+        Ok(registry)
+    }
+
+    // Finds and checks the directory for the given scale factor, e.g. "1", "0.1" or "30".
+    pub fn dataset(&self, scale_factor: &str) -> Result<Dataset, String> {
+        let dir_name = format!("social_network_sf{}", scale_factor);
+        let dir = if let Some(dir) = self.scale_factors.get(scale_factor) {
+            dir.clone()
+        } else if let Some(ref home) = self.data_home {
+            home.join(&dir_name)
+        } else if let Some(ref home) = self.config_data_home {
+            home.join(&dir_name)
+        } else {
+            return Err(format!("No location for LDBC scale factor {}: set {} to the directory holding {}, or add 'sf{} = <directory>' to {}",
+                               scale_factor, DATA_HOME_VAR, dir_name, scale_factor, DEFAULT_CONFIG));
+        };
+        Dataset::at(dir).map_err(|e| format!("LDBC scale factor {}: {}", scale_factor, e))
+    }
+}
+
+impl Dataset {
+    // Checks that DIR holds a social network.
+    pub fn at<P: AsRef<Path>>(dir: P) -> Result<Dataset, String> {
+        let dir = dir.as_ref().to_path_buf();
+        if !dir.is_dir() {
+            return Err(format!("{} is not a directory", dir.display()));
+        }
+        let missing: Vec<&str> = [NODES_FILE, EDGES_FILE].iter().cloned().filter(|f| !dir.join(f).is_file()).collect();
+        if !missing.is_empty() {
+            return Err(format!("{} is missing {}", dir.display(), missing.join(" and ")));
+        }
+        Ok(Dataset { dir })
+    }
+
+    pub fn nodes_path(&self) -> String {
+        self.dir.join(NODES_FILE).to_string_lossy().into_owned()
+    }
+
+    pub fn edges_path(&self) -> String {
+        self.dir.join(EDGES_FILE).to_string_lossy().into_owned()
+    }
+}
+
+// Finds the dataset for a scale factor using the registry from the environment.
+pub fn dataset(scale_factor: &str) -> Result<Dataset, String> {
+    Registry::from_env()?.dataset(scale_factor)
+}
@@ -0,0 +1,79 @@
+// Differential checking of cracker selects.
+// Every table's selections are compared, as multisets, against a scan over the original column.
+
+use decomposed_cracking;
+use recognitive_compression;
+use compactive_compression;
+use underswap_rle_compression;
+use overswap_rle_compression;
+
+// Returns the positions in COLUMN which hold X, in ascending order.
+pub fn scan_specific(column: &[i64], x: i64) -> Vec<i64> {
+    (0..column.len()).filter(|&i| column[i] == x).map(|i| i as i64).collect()
+}
+
+// Runs QUERIES against a table cracked on COLUMN, where the other column of the table holds each
+// tuple's position. Every selection must hold exactly the positions a scan finds.
+pub fn check_against_scan<F>(name: &str, column: &[i64], queries: &[i64], mut select: F) -> Result<(), String> where F: FnMut(i64) -> Vec<i64> {
+    for (i, &x) in queries.iter().enumerate() {
+        let mut actual = select(x);
+        actual.sort();
+        let expected = scan_specific(column, x);
+        if actual != expected {
+            return Err(format!("{}: query {} selecting {} gave {:?} but a scan gives {:?}", name, i, x, actual, expected));
+        }
+    }
+    Ok(())
+}
+
+// Checks the selections of every table type against a scan, returning the first mismatch.
+pub fn check_select_specific(column: &[i64], queries: &[i64]) -> Result<(), String> {
+    if column.is_empty() {
+        return Ok(());
+    }
+    let positions: Vec<i64> = (0..column.len() as i64).collect();
+    let mut decracked = decomposed_cracking::from_adjacency_vectors(column.to_vec(), positions.clone(), "src");
+    let mut reco = recognitive_compression::from_adjacency_vectors(column.to_vec(), positions.clone(), "src");
+    let mut coco = compactive_compression::from_adjacency_vectors(column.to_vec(), positions.clone(), "src");
+    let mut underswap = underswap_rle_compression::from_adjacency_vectors(column.to_vec(), positions.clone(), "src");
+    let mut overswap = overswap_rle_compression::from_adjacency_vectors(column.to_vec(), positions, "src");
+
+    check_against_scan("Decracked", column, queries, |x| decracked.cracker_select_specific(x, "dst"))?;
+    check_against_scan("Reco", column, queries, |x| reco.cracker_select_specific(x, "dst"))?;
+    check_against_scan("Coco", column, queries, |x| coco.cracker_select_specific(x, "dst"))?;
+    check_against_scan("Underswap", column, queries, |x| underswap.cracker_select_specific(x, "dst"))?;
+    check_against_scan("Overswap", column, queries, |x| overswap.cracker_select_specific(x, "dst"))?;
+
+    decracked.validate().map_err(|e| format!("Decracked: {}", e))?;
+    reco.validate().map_err(|e| format!("Reco: {}", e))?;
+    coco.validate().map_err(|e| format!("Coco: {}", e))?;
+    underswap.validate().map_err(|e| format!("Underswap: {}", e))?;
+    overswap.validate().map_err(|e| format!("Overswap: {}", e))
+}
+
+// Workloads are encoded as bytes for the fuzz targets and their corpus: a little-endian u16 giving
+// the length of the column, then the column's values, then the queries, each as a little-endian i16.
+
+pub fn encode_workload(column: &[i64], queries: &[i64]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(2 + 2 * (column.len() + queries.len()));
+    bytes.extend_from_slice(&(column.len() as u16).to_le_bytes());
+    for &x in column.iter().chain(queries.iter()) {
+        bytes.extend_from_slice(&(x as i16).to_le_bytes());
+    }
+    bytes
+}
+
+// Decodes a workload, ignoring a trailing odd byte and clamping the column length to the values
+// available.
+pub fn decode_workload(bytes: &[u8]) -> (Vec<i64>, Vec<i64>) {
+    if bytes.len() < 2 {
+        return (vec![], vec![]);
+    }
+    let n = u16::from_le_bytes([bytes[0], bytes[1]]) as usize;
+    let values: Vec<i64> = bytes[2..].chunks(2)
+        .filter(|c| c.len() == 2)
+        .map(|c| i16::from_le_bytes([c[0], c[1]]) as i64)
+        .collect();
+    let n = n.min(values.len());
+    (values[..n].to_vec(), values[n..].to_vec())
+}
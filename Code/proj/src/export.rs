@@ -0,0 +1,251 @@
+// Graph export
+// Writes the edges of an adjacency list, such as a table's src and dst columns, as DOT, GraphML
+// or JSON for standard graph viewers. Vertices can carry named attributes, such as their ranks or
+// BFS depths, and the export can be restricted to a subgraph, such as a vertex's neighbourhood.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Write as FmtWrite;
+use std::fs;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl From<i64> for Value {
+    fn from(x: i64) -> Value { Value::Int(x) }
+}
+
+impl From<usize> for Value {
+    fn from(x: usize) -> Value { Value::Int(x as i64) }
+}
+
+impl From<f64> for Value {
+    fn from(x: f64) -> Value { Value::Float(x) }
+}
+
+impl From<String> for Value {
+    fn from(x: String) -> Value { Value::Text(x) }
+}
+
+impl<'a> From<&'a str> for Value {
+    fn from(x: &'a str) -> Value { Value::Text(x.to_string()) }
+}
+
+impl Value {
+    fn to_text(&self) -> String {
+        match *self {
+            Value::Int(x) => x.to_string(),
+            Value::Float(x) => x.to_string(),
+            Value::Text(ref x) => x.clone(),
+        }
+    }
+
+    fn to_json(&self) -> String {
+        match *self {
+            Value::Int(x) => x.to_string(),
+            Value::Float(x) if x.is_finite() => x.to_string(),
+            Value::Float(_) => "null".to_string(),
+            Value::Text(ref x) => json_string(x),
+        }
+    }
+
+    fn graphml_type(&self) -> &'static str {
+        match *self {
+            Value::Int(_) => "long",
+            Value::Float(_) => "double",
+            Value::Text(_) => "string",
+        }
+    }
+}
+
+pub struct Export<'a> {
+    src: &'a [i64],
+    dst: &'a [i64],
+    attributes: Vec<(String, HashMap<i64, Value>)>,
+    subgraph: Option<HashSet<i64>>,
+}
+
+impl<'a> Export<'a> {
+    pub fn new(src: &'a [i64], dst: &'a [i64]) -> Export<'a> {
+        if src.len() != dst.len() {
+            panic!("new: {} sources but {} destinations", src.len(), dst.len());
+        }
+        Export { src, dst, attributes: Vec::new(), subgraph: None }
+    }
+
+    // Gives vertices the named attribute. Vertices missing from VALUES are exported without it.
+    pub fn add_attribute<V: Into<Value> + Clone>(&mut self, name: &str, values: &HashMap<i64, V>) {
+        let values = values.iter().map(|(&v, x)| (v, x.clone().into())).collect();
+        self.attributes.push((name.to_string(), values));
+    }
+
+    // Restricts the export to the given vertices and the edges between them.
+    pub fn restrict_to(&mut self, vertices: HashSet<i64>) {
+        self.subgraph = Some(vertices);
+    }
+
+    // Restricts the export to the vertices within HOPS outgoing edges of START.
+    pub fn restrict_to_neighbourhood(&mut self, start: i64, hops: usize) {
+        let vertices = neighbourhood(self.src, self.dst, start, hops);
+        self.restrict_to(vertices);
+    }
+
+    fn included(&self, v: i64) -> bool {
+        self.subgraph.as_ref().is_none_or(|s| s.contains(&v))
+    }
+
+    fn edges(&self) -> Vec<(i64, i64)> {
+        (0..self.src.len())
+            .filter(|&i| self.included(self.src[i]) && self.included(self.dst[i]))
+            .map(|i| (self.src[i], self.dst[i]))
+            .collect()
+    }
+
+    // The exported vertices in ascending order: those in the subgraph if there is one, otherwise
+    // those with edges or attributes.
+    fn vertices(&self) -> Vec<i64> {
+        let vertices: BTreeSet<i64> = match self.subgraph {
+            Some(ref s) => s.iter().cloned().collect(),
+            None => self.src.iter().chain(self.dst.iter()).cloned()
+                .chain(self.attributes.iter().flat_map(|a| a.1.keys().cloned()))
+                .collect(),
+        };
+        vertices.into_iter().collect()
+    }
+
+    fn vertex_attributes(&self, v: i64) -> Vec<(&str, &Value)> {
+        self.attributes.iter().filter_map(|a| a.1.get(&v).map(|x| (a.0.as_str(), x))).collect()
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph G {\n");
+        for v in self.vertices() {
+            let attributes = self.vertex_attributes(v);
+            let mut label = v.to_string();
+            for &(name, x) in &attributes {
+                write!(label, "\n{}={}", name, x.to_text()).unwrap();
+            }
+            write!(out, "  {} [label={}", v, dot_string(&label)).unwrap();
+            for &(name, x) in &attributes {
+                write!(out, ", {}={}", dot_string(name), dot_string(&x.to_text())).unwrap();
+            }
+            out.push_str("];\n");
+        }
+        for (src, dst) in self.edges() {
+            writeln!(out, "  {} -> {};", src, dst).unwrap();
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        for (k, (name, values)) in self.attributes.iter().enumerate() {
+            // An attribute takes the type of its values, or is a string if they're mixed.
+            let mut types: Vec<&str> = values.values().map(|x| x.graphml_type()).collect();
+            types.sort();
+            types.dedup();
+            let t = if types.len() == 1 { types[0] } else { "string" };
+            writeln!(out, "  <key id=\"d{}\" for=\"node\" attr.name=\"{}\" attr.type=\"{}\"/>", k, xml_escape(name), t).unwrap();
+        }
+        out.push_str("  <graph id=\"G\" edgedefault=\"directed\">\n");
+        for v in self.vertices() {
+            write!(out, "    <node id=\"n{}\"", v).unwrap();
+            let data: Vec<String> = self.attributes.iter().enumerate()
+                .filter_map(|(k, a)| a.1.get(&v).map(|x| format!("<data key=\"d{}\">{}</data>", k, xml_escape(&x.to_text()))))
+                .collect();
+            if data.is_empty() {
+                out.push_str("/>\n");
+            } else {
+                writeln!(out, ">{}</node>", data.concat()).unwrap();
+            }
+        }
+        for (i, (src, dst)) in self.edges().into_iter().enumerate() {
+            writeln!(out, "    <edge id=\"e{}\" source=\"n{}\" target=\"n{}\"/>", i, src, dst).unwrap();
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+
+    // The node-link format read by d3 and networkx.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"directed\": true, \"nodes\": [");
+        for (i, v) in self.vertices().into_iter().enumerate() {
+            if i > 0 {
+                out.push_str(", ");
+            }
+            write!(out, "{{\"id\": {}", v).unwrap();
+            for (name, x) in self.vertex_attributes(v) {
+                write!(out, ", {}: {}", json_string(name), x.to_json()).unwrap();
+            }
+            out.push('}');
+        }
+        out.push_str("], \"links\": [");
+        for (i, (src, dst)) in self.edges().into_iter().enumerate() {
+            if i > 0 {
+                out.push_str(", ");
+            }
+            write!(out, "{{\"source\": {}, \"target\": {}}}", src, dst).unwrap();
+        }
+        out.push_str("]}\n");
+        out
+    }
+
+    pub fn write_dot(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_dot()).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn write_graphml(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_graphml()).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn write_json(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_json()).map_err(|e| format!("{}: {}", path, e))
+    }
+}
+
+// The vertices within HOPS outgoing edges of START, including START.
+pub fn neighbourhood(src: &[i64], dst: &[i64], start: i64, hops: usize) -> HashSet<i64> {
+    let mut reached: HashSet<i64> = HashSet::new();
+    reached.insert(start);
+    let mut frontier = vec![start];
+    for _ in 0..hops {
+        let current: HashSet<i64> = frontier.drain(..).collect();
+        for i in 0..src.len() {
+            if current.contains(&src[i]) && reached.insert(dst[i]) {
+                frontier.push(dst[i]);
+            }
+        }
+        if frontier.is_empty() {
+            break;
+        }
+    }
+    reached
+}
+
+fn dot_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
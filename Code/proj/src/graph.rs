@@ -0,0 +1,414 @@
+// Graph views
+// The Graph trait lets an algorithm be written once and run over every storage method. It's
+// implemented by CrackedGraph for each table type, and by the unoptimised, preclustered and CSR
+// baselines.
+// CrackedGraph can also carry edge weights, for the WeightedGraph trait.
+
+use std::collections::HashSet;
+
+use decomposed_cracking;
+use recognitive_compression;
+use compactive_compression;
+use underswap_rle_compression;
+use overswap_rle_compression;
+use vertex_dictionary::VertexDictionary;
+
+pub trait Graph {
+    // Every vertex with an edge, in ascending order.
+    fn vertices(&self) -> &[i64];
+
+    fn edge_count(&self) -> usize;
+
+    fn out_neighbours(&mut self, v: i64) -> Vec<i64>;
+
+    fn in_neighbours(&mut self, v: i64) -> Vec<i64>;
+
+    fn out_degree(&mut self, v: i64) -> usize {
+        self.out_neighbours(v).len()
+    }
+}
+
+pub trait WeightedGraph: Graph {
+    // The out-neighbours of V, each with the weight of the edge to it.
+    fn out_edges(&mut self, v: i64) -> Vec<(i64, f64)>;
+}
+
+// Edge weights. Tables hold them in an i64 column, so float weights are stored by their bits.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Weights {
+    Int(Vec<i64>),
+    Float(Vec<f64>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum WeightType {
+    Int,
+    Float,
+}
+
+impl Weights {
+    fn into_column(self) -> (WeightType, Vec<i64>) {
+        match self {
+            Weights::Int(w) => (WeightType::Int, w),
+            Weights::Float(w) => (WeightType::Float, w.into_iter().map(|x| x.to_bits() as i64).collect()),
+        }
+    }
+
+    fn count(&self) -> usize {
+        match *self {
+            Weights::Int(ref w) => w.len(),
+            Weights::Float(ref w) => w.len(),
+        }
+    }
+}
+
+fn decode_weight(weight_type: WeightType, x: i64) -> f64 {
+    match weight_type {
+        WeightType::Int => x as f64,
+        WeightType::Float => f64::from_bits(x as u64),
+    }
+}
+
+// The vertices of an adjacency list, in ascending order.
+fn distinct_vertices(src: &[i64], dst: &[i64]) -> Vec<i64> {
+    let mut vertices: Vec<i64> = src.iter().chain(dst.iter()).cloned().collect::<HashSet<i64>>().into_iter().collect();
+    vertices.sort();
+    vertices
+}
+
+// A table which holds an adjacency list in columns "src" and "dst".
+pub trait AdjacencyTable {
+    fn from_adjacency_vectors(src: Vec<i64>, dst: Vec<i64>, crk: &str) -> Self;
+
+    // Also holds a weight for each edge, in column "weight".
+    fn from_weighted_adjacency_vectors(src: Vec<i64>, dst: Vec<i64>, weight: Vec<i64>, crk: &str) -> Self;
+
+    // Selects COL from the tuples whose cracker column holds X.
+    fn select(&mut self, x: i64, col: &str) -> Vec<i64>;
+
+    // Selects each of COLS from the tuples whose cracker column holds X, in one crack, so the
+    // ith elements of each belong to the same tuple.
+    fn select_columns(&mut self, x: i64, cols: &[&str]) -> Vec<Vec<i64>>;
+}
+
+impl AdjacencyTable for decomposed_cracking::DeCrackedTable {
+    fn from_adjacency_vectors(src: Vec<i64>, dst: Vec<i64>, crk: &str) -> Self {
+        decomposed_cracking::from_adjacency_vectors(src, dst, crk)
+    }
+
+    fn from_weighted_adjacency_vectors(src: Vec<i64>, dst: Vec<i64>, weight: Vec<i64>, crk: &str) -> Self {
+        decomposed_cracking::from_weighted_adjacency_vectors(src, dst, weight, crk)
+    }
+
+    fn select(&mut self, x: i64, col: &str) -> Vec<i64> {
+        self.cracker_select_specific(x, col)
+    }
+
+    fn select_columns(&mut self, x: i64, cols: &[&str]) -> Vec<Vec<i64>> {
+        self.cracker_select_columns(x, cols)
+    }
+}
+
+impl AdjacencyTable for recognitive_compression::ReCoTable {
+    fn from_adjacency_vectors(src: Vec<i64>, dst: Vec<i64>, crk: &str) -> Self {
+        recognitive_compression::from_adjacency_vectors(src, dst, crk)
+    }
+
+    fn from_weighted_adjacency_vectors(src: Vec<i64>, dst: Vec<i64>, weight: Vec<i64>, crk: &str) -> Self {
+        recognitive_compression::from_weighted_adjacency_vectors(src, dst, weight, crk)
+    }
+
+    fn select(&mut self, x: i64, col: &str) -> Vec<i64> {
+        self.cracker_select_specific(x, col)
+    }
+
+    fn select_columns(&mut self, x: i64, cols: &[&str]) -> Vec<Vec<i64>> {
+        self.cracker_select_columns(x, cols)
+    }
+}
+
+impl AdjacencyTable for compactive_compression::CoCoTable {
+    fn from_adjacency_vectors(src: Vec<i64>, dst: Vec<i64>, crk: &str) -> Self {
+        compactive_compression::from_adjacency_vectors(src, dst, crk)
+    }
+
+    fn from_weighted_adjacency_vectors(src: Vec<i64>, dst: Vec<i64>, weight: Vec<i64>, crk: &str) -> Self {
+        compactive_compression::from_weighted_adjacency_vectors(src, dst, weight, crk)
+    }
+
+    fn select(&mut self, x: i64, col: &str) -> Vec<i64> {
+        self.cracker_select_specific(x, col)
+    }
+
+    fn select_columns(&mut self, x: i64, cols: &[&str]) -> Vec<Vec<i64>> {
+        self.cracker_select_columns(x, cols)
+    }
+}
+
+impl AdjacencyTable for underswap_rle_compression::UnderswapRLETable {
+    fn from_adjacency_vectors(src: Vec<i64>, dst: Vec<i64>, crk: &str) -> Self {
+        underswap_rle_compression::from_adjacency_vectors(src, dst, crk)
+    }
+
+    fn from_weighted_adjacency_vectors(src: Vec<i64>, dst: Vec<i64>, weight: Vec<i64>, crk: &str) -> Self {
+        underswap_rle_compression::from_weighted_adjacency_vectors(src, dst, weight, crk)
+    }
+
+    fn select(&mut self, x: i64, col: &str) -> Vec<i64> {
+        self.cracker_select_specific(x, col)
+    }
+
+    fn select_columns(&mut self, x: i64, cols: &[&str]) -> Vec<Vec<i64>> {
+        self.cracker_select_columns(x, cols)
+    }
+}
+
+impl AdjacencyTable for overswap_rle_compression::OverswapRLETable {
+    fn from_adjacency_vectors(src: Vec<i64>, dst: Vec<i64>, crk: &str) -> Self {
+        overswap_rle_compression::from_adjacency_vectors(src, dst, crk)
+    }
+
+    fn from_weighted_adjacency_vectors(src: Vec<i64>, dst: Vec<i64>, weight: Vec<i64>, crk: &str) -> Self {
+        overswap_rle_compression::from_weighted_adjacency_vectors(src, dst, weight, crk)
+    }
+
+    fn select(&mut self, x: i64, col: &str) -> Vec<i64> {
+        self.cracker_select_specific(x, col)
+    }
+
+    fn select_columns(&mut self, x: i64, cols: &[&str]) -> Vec<Vec<i64>> {
+        self.cracker_select_columns(x, cols)
+    }
+}
+
+// An adjacency list held twice in one type of table: cracked on src to find out-neighbours, and
+// cracked on dst to find in-neighbours. If it has weights, they're held by the table cracked on src.
+pub struct CrackedGraph<T> {
+    pub out_table: T,
+    pub in_table: T,
+    vertices: Vec<i64>,
+    edges: usize,
+    weight_type: Option<WeightType>,
+}
+
+impl<T: AdjacencyTable> CrackedGraph<T> {
+    pub fn new(src: Vec<i64>, dst: Vec<i64>) -> CrackedGraph<T> {
+        let vertices = distinct_vertices(&src, &dst);
+        let edges = src.len();
+        CrackedGraph {
+            in_table: T::from_adjacency_vectors(src.clone(), dst.clone(), "dst"),
+            out_table: T::from_adjacency_vectors(src, dst, "src"),
+            vertices,
+            edges,
+            weight_type: None,
+        }
+    }
+
+    pub fn with_weights(src: Vec<i64>, dst: Vec<i64>, weights: Weights) -> CrackedGraph<T> {
+        if weights.count() != src.len() {
+            panic!("with_weights: {} edges but {} weights", src.len(), weights.count());
+        }
+        let vertices = distinct_vertices(&src, &dst);
+        let edges = src.len();
+        let (weight_type, weight) = weights.into_column();
+        CrackedGraph {
+            in_table: T::from_adjacency_vectors(src.clone(), dst.clone(), "dst"),
+            out_table: T::from_weighted_adjacency_vectors(src, dst, weight, "src"),
+            vertices,
+            edges,
+            weight_type: Some(weight_type),
+        }
+    }
+}
+
+impl<T: AdjacencyTable> Graph for CrackedGraph<T> {
+    fn vertices(&self) -> &[i64] {
+        &self.vertices
+    }
+
+    fn edge_count(&self) -> usize {
+        self.edges
+    }
+
+    fn out_neighbours(&mut self, v: i64) -> Vec<i64> {
+        self.out_table.select(v, "dst")
+    }
+
+    fn in_neighbours(&mut self, v: i64) -> Vec<i64> {
+        self.in_table.select(v, "src")
+    }
+}
+
+// Edges of a graph without weights weigh 1.
+impl<T: AdjacencyTable> WeightedGraph for CrackedGraph<T> {
+    fn out_edges(&mut self, v: i64) -> Vec<(i64, f64)> {
+        match self.weight_type {
+            Some(t) => {
+                let columns = self.out_table.select_columns(v, &["dst", "weight"]);
+                columns[0].iter().zip(&columns[1]).map(|(&u, &w)| (u, decode_weight(t, w))).collect()
+            },
+            None => self.out_table.select(v, "dst").into_iter().map(|u| (u, 1.0)).collect(),
+        }
+    }
+}
+
+pub type DeCrackedGraph = CrackedGraph<decomposed_cracking::DeCrackedTable>;
+pub type ReCoGraph = CrackedGraph<recognitive_compression::ReCoTable>;
+pub type CoCoGraph = CrackedGraph<compactive_compression::CoCoTable>;
+pub type UnderswapRLEGraph = CrackedGraph<underswap_rle_compression::UnderswapRLETable>;
+pub type OverswapRLEGraph = CrackedGraph<overswap_rle_compression::OverswapRLETable>;
+
+// The unoptimised baseline, which scans the whole adjacency list for every lookup.
+pub struct ScanGraph {
+    src: Vec<i64>,
+    dst: Vec<i64>,
+    vertices: Vec<i64>,
+}
+
+impl ScanGraph {
+    pub fn new(src: Vec<i64>, dst: Vec<i64>) -> ScanGraph {
+        let vertices = distinct_vertices(&src, &dst);
+        ScanGraph { src, dst, vertices }
+    }
+}
+
+impl Graph for ScanGraph {
+    fn vertices(&self) -> &[i64] {
+        &self.vertices
+    }
+
+    fn edge_count(&self) -> usize {
+        self.src.len()
+    }
+
+    fn out_neighbours(&mut self, v: i64) -> Vec<i64> {
+        (0..self.src.len()).filter(|&i| self.src[i] == v).map(|i| self.dst[i]).collect()
+    }
+
+    fn in_neighbours(&mut self, v: i64) -> Vec<i64> {
+        (0..self.dst.len()).filter(|&i| self.dst[i] == v).map(|i| self.src[i]).collect()
+    }
+}
+
+// The preclustering baseline: the adjacency list sorted up front by src, and a copy sorted by dst,
+// with neighbours found by binary search.
+pub struct PreclusteredGraph {
+    by_src: Vec<(i64, i64)>,
+    by_dst: Vec<(i64, i64)>,
+    vertices: Vec<i64>,
+}
+
+impl PreclusteredGraph {
+    pub fn new(src: Vec<i64>, dst: Vec<i64>) -> PreclusteredGraph {
+        let vertices = distinct_vertices(&src, &dst);
+        let mut by_src: Vec<(i64, i64)> = src.into_iter().zip(dst).collect();
+        let mut by_dst: Vec<(i64, i64)> = by_src.iter().map(|&(s, d)| (d, s)).collect();
+        by_src.sort_by_key(|e| e.0);
+        by_dst.sort_by_key(|e| e.0);
+        PreclusteredGraph { by_src, by_dst, vertices }
+    }
+}
+
+// The second elements of the pairs in a slice sorted by first element whose first element is X.
+fn clustered_range(pairs: &[(i64, i64)], x: i64) -> Vec<i64> {
+    let start = pairs.partition_point(|e| e.0 < x);
+    let end = pairs.partition_point(|e| e.0 <= x);
+    pairs[start..end].iter().map(|e| e.1).collect()
+}
+
+impl Graph for PreclusteredGraph {
+    fn vertices(&self) -> &[i64] {
+        &self.vertices
+    }
+
+    fn edge_count(&self) -> usize {
+        self.by_src.len()
+    }
+
+    fn out_neighbours(&mut self, v: i64) -> Vec<i64> {
+        clustered_range(&self.by_src, v)
+    }
+
+    fn in_neighbours(&mut self, v: i64) -> Vec<i64> {
+        clustered_range(&self.by_dst, v)
+    }
+
+    fn out_degree(&mut self, v: i64) -> usize {
+        self.by_src.partition_point(|e| e.0 <= v) - self.by_src.partition_point(|e| e.0 < v)
+    }
+}
+
+// The compressed sparse row baseline. Vertices are numbered densely through a vertex dictionary,
+// and the neighbours of the vertex numbered i are at offsets[i - 1] to offsets[i] of targets.
+pub struct CsrGraph {
+    dictionary: VertexDictionary,
+    out_offsets: Vec<usize>,
+    out_targets: Vec<i64>,
+    in_offsets: Vec<usize>,
+    in_sources: Vec<i64>,
+    vertices: Vec<i64>,
+}
+
+// Builds the offsets and targets of a CSR from edges between dense IDs 1 to n, keeping the edges'
+// order within each vertex's range.
+fn compress_rows(n: usize, from: &[i64], to: &[i64]) -> (Vec<usize>, Vec<i64>) {
+    let mut offsets = vec![0; n + 1];
+    for &u in from {
+        offsets[u as usize] += 1;
+    }
+    for i in 0..n {
+        offsets[i + 1] += offsets[i];
+    }
+    let mut next = offsets.clone();
+    let mut targets = vec![0; from.len()];
+    for i in 0..from.len() {
+        let row = from[i] as usize - 1;
+        targets[next[row]] = to[i];
+        next[row] += 1;
+    }
+    (offsets, targets)
+}
+
+impl CsrGraph {
+    pub fn new(src: Vec<i64>, dst: Vec<i64>) -> CsrGraph {
+        let vertices = distinct_vertices(&src, &dst);
+        let mut dictionary = VertexDictionary::from_vertices(&vertices);
+        let (dense_src, dense_dst) = dictionary.encode_edges(&src, &dst);
+        let n = dictionary.len();
+        let (out_offsets, out_targets) = compress_rows(n, &dense_src, &dst);
+        let (in_offsets, in_sources) = compress_rows(n, &dense_dst, &src);
+        CsrGraph { dictionary, out_offsets, out_targets, in_offsets, in_sources, vertices }
+    }
+
+    fn row(&self, v: i64) -> Option<usize> {
+        self.dictionary.internal(v).map(|i| i as usize - 1)
+    }
+}
+
+impl Graph for CsrGraph {
+    fn vertices(&self) -> &[i64] {
+        &self.vertices
+    }
+
+    fn edge_count(&self) -> usize {
+        self.out_targets.len()
+    }
+
+    fn out_neighbours(&mut self, v: i64) -> Vec<i64> {
+        match self.row(v) {
+            Some(i) => self.out_targets[self.out_offsets[i]..self.out_offsets[i + 1]].to_vec(),
+            None => vec![],
+        }
+    }
+
+    fn in_neighbours(&mut self, v: i64) -> Vec<i64> {
+        match self.row(v) {
+            Some(i) => self.in_sources[self.in_offsets[i]..self.in_offsets[i + 1]].to_vec(),
+            None => vec![],
+        }
+    }
+
+    fn out_degree(&mut self, v: i64) -> usize {
+        self.row(v).map_or(0, |i| self.out_offsets[i + 1] - self.out_offsets[i])
+    }
+}
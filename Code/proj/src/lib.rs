@@ -1,13 +1,43 @@
 extern crate time;
 extern crate rand;
 extern crate bit_vec;
+extern crate csv;
+#[macro_use]
+extern crate serde_derive;
+#[cfg(test)]
+extern crate quickcheck;
 
 pub mod utils;
+pub mod quicksort;
 
 pub mod cracker_index;
 pub mod column;
-pub mod overswap_rle_compression;
+pub mod decomposed_cracking;
+pub mod recognitive_compression;
 pub mod compactive_compression;
+pub mod underswap_rle_compression;
+pub mod overswap_rle_compression;
+
+pub mod datagen;
+pub mod workload;
+pub mod bfs;
+pub mod pagerank;
+pub mod differential;
+pub mod load_graph;
+pub mod datasets;
+pub mod load_person_csv;
+pub mod load_ldbc;
+pub mod synthetic_ldbc;
+pub mod vertex_dictionary;
+pub mod export;
+pub mod graph;
+pub mod traversal;
+pub mod sssp;
+pub mod components;
+pub mod centrality;
+pub mod triangles;
+pub mod ranking;
+pub mod personrank;
 
 #[cfg(test)]
 pub mod test;
\ No newline at end of file
@@ -0,0 +1,302 @@
+// Loaders for graph file formats
+// Each produces the src and dst columns of an adjacency list, ready for any table's
+// from_adjacency_vectors, along with the edge weights if the file has them. Parse errors are
+// reported with the line they occurred on, counting from 1.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EdgeList {
+    pub src: Vec<i64>,
+    pub dst: Vec<i64>,
+    pub weights: Option<Vec<f64>>,
+    // The number of vertices the file declares, where the format has a header which does so.
+    pub nodes: Option<i64>,
+}
+
+impl EdgeList {
+    fn new() -> EdgeList {
+        EdgeList { src: Vec::new(), dst: Vec::new(), weights: None, nodes: None }
+    }
+
+    fn push(&mut self, src: i64, dst: i64, weight: Option<f64>) {
+        self.src.push(src);
+        self.dst.push(dst);
+        if let (Some(weights), Some(w)) = (self.weights.as_mut(), weight) {
+            weights.push(w);
+        }
+    }
+
+    pub fn into_adjacency_vectors(self) -> (Vec<i64>, Vec<i64>) {
+        (self.src, self.dst)
+    }
+}
+
+// Opens PATH and parses it with the given parser, prefixing any error with the path.
+fn load<F>(path: &str, parse: F) -> Result<EdgeList, String> where F: FnOnce(BufReader<File>) -> Result<EdgeList, String> {
+    let file = File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+    parse(BufReader::new(file)).map_err(|e| format!("{}: {}", path, e))
+}
+
+pub fn load_snap(path: &str) -> Result<EdgeList, String> {
+    load(path, parse_snap)
+}
+
+pub fn load_matrix_market(path: &str) -> Result<EdgeList, String> {
+    load(path, parse_matrix_market)
+}
+
+pub fn load_metis(path: &str) -> Result<EdgeList, String> {
+    load(path, parse_metis)
+}
+
+pub fn load_dimacs(path: &str) -> Result<EdgeList, String> {
+    load(path, parse_dimacs)
+}
+
+// Reads the lines of a file along with their line numbers.
+fn numbered_lines<R: BufRead>(reader: R) -> impl Iterator<Item = Result<(usize, String), String>> {
+    reader.lines().enumerate().map(|(i, line)| line.map(|l| (i + 1, l)).map_err(|e| format!("line {}: {}", i + 1, e)))
+}
+
+fn parse_field<T: FromStr>(field: Option<&str>, what: &str, line: usize) -> Result<T, String> {
+    let field = field.ok_or_else(|| format!("line {}: Missing {}", line, what))?;
+    field.parse().map_err(|_| format!("line {}: Invalid {} '{}'", line, what, field))
+}
+
+fn check_vertex(v: i64, n: i64, line: usize) -> Result<i64, String> {
+    if v < 1 || v > n {
+        return Err(format!("line {}: Vertex {} is outside of 1..{}", line, v, n));
+    }
+    Ok(v)
+}
+
+// SNAP style edge lists: one "src dst" or "src dst weight" edge per line, separated by whitespace,
+// with lines starting with '#' being comments. Either every edge has a weight or none do.
+pub fn parse_snap<R: BufRead>(reader: R) -> Result<EdgeList, String> {
+    let mut edges = EdgeList::new();
+    let mut weighted = None;
+    for line in numbered_lines(reader) {
+        let (i, line) = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let src = parse_field(fields.next(), "source vertex", i)?;
+        let dst = parse_field(fields.next(), "destination vertex", i)?;
+        let weight = match fields.next() {
+            Some(w) => Some(parse_field(Some(w), "weight", i)?),
+            None => None,
+        };
+        if fields.next().is_some() {
+            return Err(format!("line {}: Expected at most three fields", i));
+        }
+        match weighted {
+            None => {
+                weighted = Some(weight.is_some());
+                if weight.is_some() {
+                    edges.weights = Some(Vec::new());
+                }
+            },
+            Some(w) if w != weight.is_some() => {
+                return Err(format!("line {}: Some edges have weights and some don't", i));
+            },
+            _ => {},
+        }
+        edges.push(src, dst, weight);
+    }
+    Ok(edges)
+}
+
+// Matrix Market coordinate files. Each entry (i, j) of the matrix is an edge from i to j, weighted
+// by the entry's value unless the matrix is a pattern. Symmetric matrices only store the entries
+// on and below the diagonal, so the other direction of each edge is added.
+pub fn parse_matrix_market<R: BufRead>(reader: R) -> Result<EdgeList, String> {
+    let mut lines = numbered_lines(reader);
+    let mut edges = EdgeList::new();
+
+    let (i, banner) = lines.next().unwrap_or_else(|| Err("line 1: Missing %%MatrixMarket banner".to_string()))?;
+    let banner: Vec<String> = banner.split_whitespace().map(|f| f.to_lowercase()).collect();
+    if banner.len() != 5 || banner[0] != "%%matrixmarket" || banner[1] != "matrix" {
+        return Err(format!("line {}: Expected a '%%MatrixMarket matrix' banner", i));
+    }
+    if banner[2] != "coordinate" {
+        return Err(format!("line {}: Only coordinate matrices are supported, not {}", i, banner[2]));
+    }
+    let weighted = match banner[3].as_str() {
+        "pattern" => false,
+        "integer" | "real" => true,
+        field => return Err(format!("line {}: Unsupported field {}", i, field)),
+    };
+    let symmetric = match banner[4].as_str() {
+        "general" => false,
+        "symmetric" | "skew-symmetric" => true,
+        symmetry => return Err(format!("line {}: Unsupported symmetry {}", i, symmetry)),
+    };
+    let skew = banner[4] == "skew-symmetric";
+    if weighted {
+        edges.weights = Some(Vec::new());
+    }
+
+    let mut size = None;
+    let mut entries = 0;
+    let mut last = i;
+    for line in lines {
+        let (i, line) = line?;
+        last = i;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('%') {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let (rows, cols, nnz) = match size {
+            None => {
+                let rows: i64 = parse_field(fields.next(), "row count", i)?;
+                let cols: i64 = parse_field(fields.next(), "column count", i)?;
+                let nnz: usize = parse_field(fields.next(), "entry count", i)?;
+                size = Some((rows, cols, nnz));
+                edges.nodes = Some(rows.max(cols));
+                continue;
+            },
+            Some(s) => s,
+        };
+        if entries == nnz {
+            return Err(format!("line {}: More than the {} declared entries", i, nnz));
+        }
+        let src = check_vertex(parse_field(fields.next(), "row", i)?, rows, i)?;
+        let dst = check_vertex(parse_field(fields.next(), "column", i)?, cols, i)?;
+        let weight = if weighted { Some(parse_field::<f64>(fields.next(), "value", i)?) } else { None };
+        edges.push(src, dst, weight);
+        if symmetric && src != dst {
+            edges.push(dst, src, weight.map(|w| if skew { -w } else { w }));
+        }
+        entries += 1;
+    }
+    match size {
+        None => Err(format!("line {}: Missing size line", last)),
+        Some((_, _, nnz)) if entries < nnz => Err(format!("line {}: Only {} of the {} declared entries", last, entries, nnz)),
+        _ => Ok(edges),
+    }
+}
+
+// METIS graph files. After the "n m [fmt [ncon]]" header, line k lists the neighbours of vertex
+// k, preceded by its size and weights if fmt says so, and with each neighbour followed by the
+// edge's weight if fmt says so. The graph is undirected, so each of the m edges is listed from
+// both ends. Lines starting with '%' are comments.
+pub fn parse_metis<R: BufRead>(reader: R) -> Result<EdgeList, String> {
+    let mut edges = EdgeList::new();
+    let mut header = None;
+    let mut v = 0;
+    let mut last = 0;
+    for line in numbered_lines(reader) {
+        let (i, line) = line?;
+        last = i;
+        if line.trim_start().starts_with('%') {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let (n, has_size, vertex_weights, has_edge_weights) = match header {
+            None => {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let n: i64 = parse_field(fields.next(), "vertex count", i)?;
+                let m: usize = parse_field(fields.next(), "edge count", i)?;
+                let fmt = fields.next().unwrap_or("0");
+                if fmt.len() > 3 || fmt.chars().any(|c| c != '0' && c != '1') {
+                    return Err(format!("line {}: Invalid fmt '{}'", i, fmt));
+                }
+                let fmt = format!("{:0>3}", fmt);
+                let ncon: usize = match fields.next() {
+                    Some(c) => parse_field(Some(c), "constraint count", i)?,
+                    None => 1,
+                };
+                let flag = |k: usize| fmt.as_bytes()[k] == b'1';
+                let vertex_weights = if flag(1) { ncon } else { 0 };
+                header = Some((n, flag(0), vertex_weights, flag(2)));
+                edges.nodes = Some(n);
+                edges.src.reserve(2 * m);
+                edges.dst.reserve(2 * m);
+                if flag(2) {
+                    edges.weights = Some(Vec::with_capacity(2 * m));
+                }
+                continue;
+            },
+            Some(h) => h,
+        };
+        v += 1;
+        if v > n {
+            if line.trim().is_empty() {
+                continue;
+            }
+            return Err(format!("line {}: More than the {} declared vertices", i, n));
+        }
+        if has_size {
+            parse_field::<i64>(fields.next(), "vertex size", i)?;
+        }
+        for _ in 0..vertex_weights {
+            parse_field::<i64>(fields.next(), "vertex weight", i)?;
+        }
+        while let Some(field) = fields.next() {
+            let u = check_vertex(parse_field(Some(field), "neighbour", i)?, n, i)?;
+            let weight = if has_edge_weights { Some(parse_field::<f64>(fields.next(), "edge weight", i)?) } else { None };
+            edges.push(v, u, weight);
+        }
+    }
+    let n = match header {
+        None => return Err(format!("line {}: Missing header", last)),
+        Some((n, _, _, _)) => n,
+    };
+    if v < n {
+        return Err(format!("line {}: Only {} of the {} declared vertices", last, v, n));
+    }
+    Ok(edges)
+}
+
+// DIMACS shortest path files: "c" comment lines, a "p sp n m" problem line, then m "a u v w" arc
+// lines giving an edge from u to v of weight w.
+pub fn parse_dimacs<R: BufRead>(reader: R) -> Result<EdgeList, String> {
+    let mut edges = EdgeList::new();
+    edges.weights = Some(Vec::new());
+    let mut problem = None;
+    let mut last = 0;
+    for line in numbered_lines(reader) {
+        let (i, line) = line?;
+        last = i;
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            None | Some("c") => {},
+            Some("p") => {
+                if problem.is_some() {
+                    return Err(format!("line {}: Second problem line", i));
+                }
+                if fields.next() != Some("sp") {
+                    return Err(format!("line {}: Expected a 'p sp' problem line", i));
+                }
+                let n: i64 = parse_field(fields.next(), "vertex count", i)?;
+                let m: usize = parse_field(fields.next(), "arc count", i)?;
+                problem = Some((n, m));
+                edges.nodes = Some(n);
+            },
+            Some("a") => {
+                let (n, m) = problem.ok_or_else(|| format!("line {}: Arc before the problem line", i))?;
+                if edges.src.len() == m {
+                    return Err(format!("line {}: More than the {} declared arcs", i, m));
+                }
+                let src = check_vertex(parse_field(fields.next(), "source vertex", i)?, n, i)?;
+                let dst = check_vertex(parse_field(fields.next(), "destination vertex", i)?, n, i)?;
+                let weight = parse_field(fields.next(), "weight", i)?;
+                edges.push(src, dst, Some(weight));
+            },
+            Some(other) => return Err(format!("line {}: Unknown line type '{}'", i, other)),
+        }
+    }
+    match problem {
+        None => Err(format!("line {}: Missing problem line", last)),
+        Some((_, m)) if edges.src.len() < m => Err(format!("line {}: Only {} of the {} declared arcs", last, edges.src.len(), m)),
+        _ => Ok(edges),
+    }
+}
@@ -0,0 +1,294 @@
+// LDBC SNB social network loading
+// Loads the entity and relationship files of an LDBC social network directory, each into a
+// decomposed cracked table. Columns are typed by the schema below: ids and integers are stored as
+// they are, dates and datetimes as milliseconds since the Unix epoch, and text is dictionary
+// encoded, with the dictionary kept alongside the table.
+// Entity tables are cracked on "id". Relationship tables have their two ids in "src" and "dst",
+// like the tables built by from_adjacency_vectors, and are cracked on "src".
+
+use csv;
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+
+use datasets::Dataset;
+use decomposed_cracking::DeCrackedTable;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColumnType {
+    Id,
+    Int,
+    // yyyy-mm-dd
+    Date,
+    // yyyy-mm-ddThh:mm:ss.sss+zzzz
+    DateTime,
+    Text,
+}
+
+use self::ColumnType::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Relation {
+    // The file is <name>_0_0.csv.
+    pub name: &'static str,
+    pub columns: &'static [(&'static str, ColumnType)],
+}
+
+impl Relation {
+    pub fn file(&self) -> String {
+        format!("{}_0_0.csv", self.name)
+    }
+}
+
+// Entities
+pub const PERSON: Relation = Relation { name: "person", columns: &[("id", Id), ("firstName", Text), ("lastName", Text), ("gender", Text), ("birthday", Date), ("creationDate", DateTime), ("locationIP", Text), ("browserUsed", Text)] };
+pub const POST: Relation = Relation { name: "post", columns: &[("id", Id), ("imageFile", Text), ("creationDate", DateTime), ("locationIP", Text), ("browserUsed", Text), ("language", Text), ("content", Text), ("length", Int)] };
+pub const COMMENT: Relation = Relation { name: "comment", columns: &[("id", Id), ("creationDate", DateTime), ("locationIP", Text), ("browserUsed", Text), ("content", Text), ("length", Int)] };
+pub const FORUM: Relation = Relation { name: "forum", columns: &[("id", Id), ("title", Text), ("creationDate", DateTime)] };
+pub const TAG: Relation = Relation { name: "tag", columns: &[("id", Id), ("name", Text), ("url", Text)] };
+pub const TAGCLASS: Relation = Relation { name: "tagclass", columns: &[("id", Id), ("name", Text), ("url", Text)] };
+pub const PLACE: Relation = Relation { name: "place", columns: &[("id", Id), ("name", Text), ("url", Text), ("type", Text)] };
+pub const ORGANISATION: Relation = Relation { name: "organisation", columns: &[("id", Id), ("type", Text), ("name", Text), ("url", Text)] };
+
+// Relationships
+const EDGE: &[(&str, ColumnType)] = &[("src", Id), ("dst", Id)];
+const DATED_EDGE: &[(&str, ColumnType)] = &[("src", Id), ("dst", Id), ("creationDate", DateTime)];
+
+pub const COMMENT_HAS_CREATOR_PERSON: Relation = Relation { name: "comment_hasCreator_person", columns: EDGE };
+pub const COMMENT_HAS_TAG_TAG: Relation = Relation { name: "comment_hasTag_tag", columns: EDGE };
+pub const COMMENT_IS_LOCATED_IN_PLACE: Relation = Relation { name: "comment_isLocatedIn_place", columns: EDGE };
+pub const COMMENT_REPLY_OF_COMMENT: Relation = Relation { name: "comment_replyOf_comment", columns: EDGE };
+pub const COMMENT_REPLY_OF_POST: Relation = Relation { name: "comment_replyOf_post", columns: EDGE };
+pub const FORUM_CONTAINER_OF_POST: Relation = Relation { name: "forum_containerOf_post", columns: EDGE };
+pub const FORUM_HAS_MEMBER_PERSON: Relation = Relation { name: "forum_hasMember_person", columns: &[("src", Id), ("dst", Id), ("joinDate", DateTime)] };
+pub const FORUM_HAS_MODERATOR_PERSON: Relation = Relation { name: "forum_hasModerator_person", columns: EDGE };
+pub const FORUM_HAS_TAG_TAG: Relation = Relation { name: "forum_hasTag_tag", columns: EDGE };
+pub const ORGANISATION_IS_LOCATED_IN_PLACE: Relation = Relation { name: "organisation_isLocatedIn_place", columns: EDGE };
+pub const PERSON_HAS_INTEREST_TAG: Relation = Relation { name: "person_hasInterest_tag", columns: EDGE };
+pub const PERSON_IS_LOCATED_IN_PLACE: Relation = Relation { name: "person_isLocatedIn_place", columns: EDGE };
+pub const PERSON_KNOWS_PERSON: Relation = Relation { name: "person_knows_person", columns: DATED_EDGE };
+pub const PERSON_LIKES_COMMENT: Relation = Relation { name: "person_likes_comment", columns: DATED_EDGE };
+pub const PERSON_LIKES_POST: Relation = Relation { name: "person_likes_post", columns: DATED_EDGE };
+pub const PERSON_STUDY_AT_ORGANISATION: Relation = Relation { name: "person_studyAt_organisation", columns: &[("src", Id), ("dst", Id), ("classYear", Int)] };
+pub const PERSON_WORK_AT_ORGANISATION: Relation = Relation { name: "person_workAt_organisation", columns: &[("src", Id), ("dst", Id), ("workFrom", Int)] };
+pub const PERSON_EMAIL_EMAILADDRESS: Relation = Relation { name: "person_email_emailaddress", columns: &[("src", Id), ("email", Text)] };
+pub const PERSON_SPEAKS_LANGUAGE: Relation = Relation { name: "person_speaks_language", columns: &[("src", Id), ("language", Text)] };
+pub const PLACE_IS_PART_OF_PLACE: Relation = Relation { name: "place_isPartOf_place", columns: EDGE };
+pub const POST_HAS_CREATOR_PERSON: Relation = Relation { name: "post_hasCreator_person", columns: EDGE };
+pub const POST_HAS_TAG_TAG: Relation = Relation { name: "post_hasTag_tag", columns: EDGE };
+pub const POST_IS_LOCATED_IN_PLACE: Relation = Relation { name: "post_isLocatedIn_place", columns: EDGE };
+pub const TAG_HAS_TYPE_TAGCLASS: Relation = Relation { name: "tag_hasType_tagclass", columns: EDGE };
+pub const TAGCLASS_IS_SUBCLASS_OF_TAGCLASS: Relation = Relation { name: "tagclass_isSubclassOf_tagclass", columns: EDGE };
+
+pub const SOCIAL_NETWORK: &[Relation] = &[
+    PERSON, POST, COMMENT, FORUM, TAG, TAGCLASS, PLACE, ORGANISATION,
+    COMMENT_HAS_CREATOR_PERSON, COMMENT_HAS_TAG_TAG, COMMENT_IS_LOCATED_IN_PLACE, COMMENT_REPLY_OF_COMMENT,
+    COMMENT_REPLY_OF_POST, FORUM_CONTAINER_OF_POST, FORUM_HAS_MEMBER_PERSON, FORUM_HAS_MODERATOR_PERSON,
+    FORUM_HAS_TAG_TAG, ORGANISATION_IS_LOCATED_IN_PLACE, PERSON_HAS_INTEREST_TAG, PERSON_IS_LOCATED_IN_PLACE,
+    PERSON_KNOWS_PERSON, PERSON_LIKES_COMMENT, PERSON_LIKES_POST, PERSON_STUDY_AT_ORGANISATION,
+    PERSON_WORK_AT_ORGANISATION, PERSON_EMAIL_EMAILADDRESS, PERSON_SPEAKS_LANGUAGE, PLACE_IS_PART_OF_PLACE,
+    POST_HAS_CREATOR_PERSON, POST_HAS_TAG_TAG, POST_IS_LOCATED_IN_PLACE, TAG_HAS_TYPE_TAGCLASS,
+    TAGCLASS_IS_SUBCLASS_OF_TAGCLASS,
+];
+
+// Maps each distinct string in a text column to a code, in the order the strings were first seen.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Dictionary {
+    pub codes: HashMap<String, i64>,
+    pub values: Vec<String>,
+}
+
+impl Dictionary {
+    pub fn encode(&mut self, s: &str) -> i64 {
+        if let Some(&code) = self.codes.get(s) {
+            return code;
+        }
+        let code = self.values.len() as i64;
+        self.codes.insert(s.to_string(), code);
+        self.values.push(s.to_string());
+        code
+    }
+
+    pub fn code(&self, s: &str) -> Option<i64> {
+        self.codes.get(s).cloned()
+    }
+
+    pub fn decode(&self, code: i64) -> Option<&str> {
+        self.values.get(code as usize).map(|s| s.as_str())
+    }
+}
+
+pub struct LdbcTable {
+    pub relation: Relation,
+    pub table: DeCrackedTable,
+    // The dictionary of each text column.
+    pub dictionaries: HashMap<String, Dictionary>,
+}
+
+impl LdbcTable {
+    pub fn column_type(&self, col: &str) -> Option<ColumnType> {
+        self.relation.columns.iter().find(|c| c.0 == col).map(|c| c.1)
+    }
+
+    // Selects COL from the tuples whose cracker column holds X, decoding text.
+    pub fn select_text(&mut self, x: i64, col: &str) -> Vec<String> {
+        if self.column_type(col) != Some(Text) {
+            panic!("select_text: {} has no text column {}", self.relation.name, col);
+        }
+        let codes = self.table.cracker_select_specific(x, col);
+        let dictionary = &self.dictionaries[col];
+        codes.iter().map(|&c| dictionary.decode(c).unwrap().to_string()).collect()
+    }
+}
+
+pub fn load_relation(dataset: &Dataset, relation: &Relation) -> Result<LdbcTable, String> {
+    let path = dataset.dir.join(relation.file());
+    let file = File::open(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    read_relation(file, relation).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// Loads every file of the social network, keyed by relation name.
+pub fn load_social_network(dataset: &Dataset) -> Result<HashMap<&'static str, LdbcTable>, String> {
+    let mut tables = HashMap::new();
+    for relation in SOCIAL_NETWORK {
+        tables.insert(relation.name, load_relation(dataset, relation)?);
+    }
+    Ok(tables)
+}
+
+// Reads a pipe separated file, with a header line, of the given relation.
+pub fn read_relation<R: Read>(reader: R, relation: &Relation) -> Result<LdbcTable, String> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .delimiter(b'|')
+        .quoting(false)
+        .from_reader(reader);
+    let header = rdr.headers().map_err(|e| format!("line 1: {}", e))?;
+    if header.len() != relation.columns.len() {
+        return Err(format!("line 1: Expected the {} columns of {}, found {}", relation.columns.len(), relation.name, header.len()));
+    }
+
+    let mut columns: Vec<Vec<i64>> = vec![Vec::new(); relation.columns.len()];
+    let mut dictionaries: HashMap<String, Dictionary> = relation.columns.iter()
+        .filter(|c| c.1 == Text)
+        .map(|c| (c.0.to_string(), Dictionary::default()))
+        .collect();
+    for result in rdr.records() {
+        let record = result.map_err(|e| match e.position() {
+            Some(p) => format!("line {}: {}", p.line(), e),
+            None => e.to_string(),
+        })?;
+        let line = record.position().map(|p| p.line()).unwrap_or(0);
+        for (i, &(name, column_type)) in relation.columns.iter().enumerate() {
+            let field = record.get(i).unwrap();
+            let value = match column_type {
+                Id | Int => field.parse::<i64>().map_err(|_| format!("line {}: Invalid {} '{}'", line, name, field))?,
+                Date | DateTime => parse_timestamp(field).map_err(|e| format!("line {}: Invalid {} '{}': {}", line, name, field, e))?,
+                Text => dictionaries.get_mut(name).unwrap().encode(field),
+            };
+            columns[i].push(value);
+        }
+    }
+
+    let mut table = DeCrackedTable::new();
+    let names: Vec<String> = relation.columns.iter().map(|c| c.0.to_string()).collect();
+    table.new_columns(names.clone());
+    let mut values: HashMap<String, Vec<i64>> = names.iter().cloned().zip(columns).collect();
+    table.insert(&mut values);
+    table.set_crk_col(names[0].clone());
+    Ok(LdbcTable { relation: *relation, table, dictionaries })
+}
+
+// Parses an LDBC date or datetime into milliseconds since the Unix epoch. Datetimes may have a
+// +hhmm, +hh:mm or Z offset, and dates already given as milliseconds are accepted as they are.
+pub fn parse_timestamp(s: &str) -> Result<i64, String> {
+    if let Ok(ms) = s.parse::<i64>() {
+        return Ok(ms);
+    }
+    let (date, time) = match s.find('T') {
+        Some(t) => (&s[..t], Some(&s[t + 1..])),
+        None => (s, None),
+    };
+    let ymd: Vec<&str> = date.split('-').collect();
+    if ymd.len() != 3 {
+        return Err("Expected yyyy-mm-dd".to_string());
+    }
+    let y: i64 = ymd[0].parse().map_err(|_| "Invalid year")?;
+    let m: i64 = ymd[1].parse().map_err(|_| "Invalid month")?;
+    let d: i64 = ymd[2].parse().map_err(|_| "Invalid day")?;
+    if !(1..=12).contains(&m) || !(1..=days_in_month(y, m)).contains(&d) {
+        return Err("Month or day out of range".to_string());
+    }
+    let mut ms = days_from_civil(y, m, d) * 86_400_000;
+
+    if let Some(time) = time {
+        let (clock, offset) = match time.find(&['+', '-', 'Z'][..]) {
+            Some(z) => (&time[..z], &time[z..]),
+            None => (time, ""),
+        };
+        let hms: Vec<&str> = clock.split(':').collect();
+        if hms.len() != 3 {
+            return Err("Expected hh:mm:ss".to_string());
+        }
+        let h: i64 = hms[0].parse().map_err(|_| "Invalid hour")?;
+        let min: i64 = hms[1].parse().map_err(|_| "Invalid minute")?;
+        let sec: f64 = hms[2].parse().map_err(|_| "Invalid second")?;
+        ms += (h * 60 + min) * 60_000 + (sec * 1000.0).round() as i64;
+
+        if offset.len() > 1 {
+            let sign = if offset.starts_with('-') { -1 } else { 1 };
+            let digits: String = offset[1..].chars().filter(|&c| c != ':').collect();
+            if digits.len() != 4 {
+                return Err("Expected a +hhmm offset".to_string());
+            }
+            let oh: i64 = digits[..2].parse().map_err(|_| "Invalid offset")?;
+            let om: i64 = digits[2..].parse().map_err(|_| "Invalid offset")?;
+            ms -= sign * (oh * 60 + om) * 60_000;
+        }
+    }
+    Ok(ms)
+}
+
+// Formats milliseconds since the Unix epoch as an LDBC datetime, or as a date when DATE_ONLY.
+pub fn format_timestamp(ms: i64, date_only: bool) -> String {
+    let days = ms.div_euclid(86_400_000);
+    let (y, m, d) = civil_from_days(days);
+    if date_only {
+        return format!("{:04}-{:02}-{:02}", y, m, d);
+    }
+    let t = ms.rem_euclid(86_400_000);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}+0000", y, m, d, t / 3_600_000, t / 60_000 % 60, t / 1000 % 60, t % 1000)
+}
+
+// The number of days in month m of year y in the proleptic Gregorian calendar.
+fn days_in_month(y: i64, m: i64) -> i64 {
+    match m {
+        2 if y % 4 == 0 && (y % 100 != 0 || y % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Days from 1970-01-01 to the given date in the proleptic Gregorian calendar.
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let doy = (153 * ((m + 9) % 12) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+// The date which is the given number of days from 1970-01-01, as (year, month, day).
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    (yoe + era * 400 + if m <= 2 { 1 } else { 0 }, m, d)
+}
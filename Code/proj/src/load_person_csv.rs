@@ -3,44 +3,17 @@ use csv;
 use std::error::Error;
 use std::fs::File;
 
+use datasets;
+use synthetic_ldbc;
+
 // Nodes @ social_network/person_0_0.csv
 // Edges @ social_network/person_knows_person_0_0.csv
-
-// SET THIS WHEN DOING EXPERIMENTS
-pub fn cur_data_home() -> &'static str {
-    uni_data_home()
-}
-
-pub fn uni_data_home() -> &'static str {
-    "/homes/rrm115/Data"
-}
-
-pub fn home_data_home() -> &'static str {
-    "/home/rob/S3G2/ldbc_snb_datagen"
-}
-
-pub fn social_network_sf1() -> String {
-    cur_data_home().to_string() + "/social_network_sf1"
-}
-
-pub fn social_network_sf3() -> String {
-    cur_data_home().to_string() + "/social_network_sf3"
-}
-
-pub fn social_network_sf10() -> String {
-    cur_data_home().to_string() + "/social_network_sf10"
-}
-
-pub fn nodes(social_network: String) -> String {
-    social_network + "/person_0_0.csv"
-}
-
-pub fn edges(social_network: String) -> String {
-    social_network + "/person_knows_person_0_0.csv"
-}
+// The social_network directory for each scale factor is found through the datasets registry.
 
 // NODES/PEOPLE
 
+// Fields are named as the CSV headers are, and read whether or not they're used.
+#[allow(dead_code, non_snake_case)]
 #[derive(Deserialize)]
 struct RawPerson {
     id: i64,
@@ -60,7 +33,7 @@ pub struct Person {
     pub last_name: String,
 }
 
-fn read_people(file_path: String) -> Result<Vec<Person>, Box<Error>> {
+fn read_people(file_path: String) -> Result<Vec<Person>, Box<dyn Error>> {
     let file = File::open(file_path)?;
     let mut rdr = csv::ReaderBuilder::new()
         .delimiter(b'|')
@@ -77,14 +50,15 @@ fn read_people(file_path: String) -> Result<Vec<Person>, Box<Error>> {
 }
 
 pub fn read_nodes(file_path: String) -> Vec<Person> {
-    match read_people(file_path) {
+    match read_people(file_path.clone()) {
         Ok(people) => people,
-        Err(_err)   => panic!(),
+        Err(err)   => panic!("read_nodes: {}: {}", file_path, err),
     }
 }
 
 // EDGES/FRIENDSHIPS
 
+#[allow(dead_code, non_snake_case)]
 #[derive(Debug,Deserialize)]
 struct RawFriendship {
     p1id: i64,
@@ -97,7 +71,7 @@ pub struct Friendship {
     pub p2id: i64,
 }
 
-fn read_friendships(file_path: String) -> Result<Vec<Friendship>, Box<Error>> {
+fn read_friendships(file_path: String) -> Result<Vec<Friendship>, Box<dyn Error>> {
     let file = File::open(file_path)?;
     let mut rdr = csv::ReaderBuilder::new()
         .has_headers(false)
@@ -115,7 +89,7 @@ fn read_friendships(file_path: String) -> Result<Vec<Friendship>, Box<Error>> {
     Ok(friendships)
 }
 
-fn read_friendships_adjl(file_path: String) -> Result<(Vec<i64>, Vec<i64>), Box<Error>> {
+fn read_friendships_adjl(file_path: String) -> Result<(Vec<i64>, Vec<i64>), Box<dyn Error>> {
     let file = File::open(file_path)?;
     let mut rdr = csv::ReaderBuilder::new()
         .has_headers(false)
@@ -135,66 +109,70 @@ fn read_friendships_adjl(file_path: String) -> Result<(Vec<i64>, Vec<i64>), Box<
 }
 
 pub fn read_edges(file_path: String) -> Vec<Friendship> {
-    match read_friendships(file_path) {
+    match read_friendships(file_path.clone()) {
         Ok(friendships) => friendships,
-        Err(_err)        => panic!(),
+        Err(err)         => panic!("read_edges: {}: {}", file_path, err),
     }
 }
 
 pub fn read_edges_adjl(file_path: String) -> (Vec<i64>, Vec<i64>) {
-    match read_friendships_adjl(file_path) {
+    match read_friendships_adjl(file_path.clone()) {
         Ok((src, dst)) => (src, dst),
-        Err(_err)        => panic!(),
+        Err(err)         => panic!("read_edges_adjl: {}: {}", file_path, err),
     }
 }
 
 // Data Access
 
-pub fn sf1_nodes() -> Vec<Person> {
-    read_nodes(nodes(social_network_sf1()))
-}
-
-pub fn sf1_edges() -> Vec<Friendship> {
-    read_edges(edges(social_network_sf1()))
-}
-
-pub fn sf1_edges_adjl() -> (Vec<i64>, Vec<i64>) {
-    read_edges_adjl(edges(social_network_sf1()))
+// Finds the social network for a scale factor, e.g. "1", "0.1" or "30", panicking with the reason
+// if it can't be found.
+pub fn social_network(scale_factor: &str) -> datasets::Dataset {
+    datasets::dataset(scale_factor).unwrap_or_else(|e| panic!("social_network: {}", e))
 }
 
-pub fn sf3_nodes() -> Vec<Person> {
-    read_nodes(nodes(social_network_sf3()))
+pub fn sf_nodes(scale_factor: &str) -> Vec<Person> {
+    read_nodes(social_network(scale_factor).nodes_path())
 }
 
-pub fn sf3_edges() -> Vec<Friendship> {
-    read_edges(edges(social_network_sf3()))
+pub fn sf_edges(scale_factor: &str) -> Vec<Friendship> {
+    read_edges(social_network(scale_factor).edges_path())
 }
 
-pub fn sf3_edges_adjl() -> (Vec<i64>, Vec<i64>) {
-    read_edges_adjl(edges(social_network_sf3()))
+pub fn sf_edges_adjl(scale_factor: &str) -> (Vec<i64>, Vec<i64>) {
+    read_edges_adjl(social_network(scale_factor).edges_path())
 }
 
-pub fn sf10_nodes() -> Vec<Person> {
-    read_nodes(nodes(social_network_sf10()))
+// Where the people and their friendships come from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PersonSource<'a> {
+    // The LDBC files for a scale factor, found through the datasets registry.
+    Ldbc(&'a str),
+    // A synthetic_ldbc network of the given scale factor, generated from the given seed.
+    Synthetic(f64, usize),
 }
 
-pub fn sf10_edges() -> Vec<Friendship> {
-    read_edges(edges(social_network_sf10()))
-}
-
-pub fn sf10_edges_adjl() -> (Vec<i64>, Vec<i64>) {
-    read_edges_adjl(edges(social_network_sf10()))
+pub fn load_people(source: PersonSource) -> (Vec<Person>, (Vec<i64>, Vec<i64>)) {
+    match source {
+        PersonSource::Ldbc(sf) => (sf_nodes(sf), sf_edges_adjl(sf)),
+        PersonSource::Synthetic(sf, seed) => {
+            let network = synthetic_ldbc::generate(sf, seed);
+            let people = network.people.into_iter()
+                .map(|p| Person { id: p.id, first_name: p.first_name, last_name: p.last_name })
+                .collect();
+            (people, (network.src, network.dst))
+        },
+    }
 }
 
 pub fn test() {
-    let people = sf1_nodes();
+    let people = sf_nodes("1");
     for i in 0..10 {
         println!("{:?}", people[i]);
     }
     println!("...");
     println!("Successfully read {} people", people.len());
 
-    let (src, dst) = sf1_edges_adjl();
+    let (src, dst) = sf_edges_adjl("1");
     for i in 0..10 {
         println!("{} -> {}", src[i], dst[i]);
     }
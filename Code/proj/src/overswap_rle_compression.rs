@@ -7,6 +7,7 @@ use column::Column;
 use column::IntCol;
 use cracker_index::AVLCrackerIndex;
 use std::collections::HashMap;
+use std::ops::Range;
 use std::slice::Iter;
 
 #[derive(Clone)]
@@ -152,6 +153,24 @@ impl OverswapRLETable {
 
     // Returns the elements of T where the cracker columns's value equals X
     pub fn cracker_select_specific(&mut self, x: i64, col: &str) -> Vec<i64> {
+        let piece = self.crack_select_specific(x);
+        #[cfg(feature = "validate")]
+        self.validate().unwrap_or_else(|e| panic!("cracker_select_specific({}): {}", x, e));
+        self.get_values(self.crk_col.base_idx[piece].iter(), col)
+    }
+
+    // Returns the elements of each of COLS where the cracker column's value equals X. They are
+    // all read from the same piece, so the ith elements of each belong to the same tuple.
+    pub fn cracker_select_columns(&mut self, x: i64, cols: &[&str]) -> Vec<Vec<i64>> {
+        let piece = self.crack_select_specific(x);
+        #[cfg(feature = "validate")]
+        self.validate().unwrap_or_else(|e| panic!("cracker_select_columns({}): {}", x, e));
+        cols.iter().map(|col| self.get_values(self.crk_col.base_idx[piece.clone()].iter(), col)).collect()
+    }
+
+    // Cracks on X and returns the positions in base_idx of the tuples where the cracker column's
+    // value equals X.
+    fn crack_select_specific(&mut self, x: i64) -> Range<usize> {
         // Init
         if self.crk_col.crk.len() == 0 {
 
@@ -160,10 +179,11 @@ impl OverswapRLETable {
         // Setup
         let mut p_low  = self.crk_col.crk_idx.lower_bound(&x).unwrap_or(0);
         if p_low == self.count {
-            return vec![];
+            return 0..0;
         }
-        let mut p_high = self.crk_col.crk_idx.upper_bound(&(x + 1)).unwrap_or(self.count) - 1;
-        if p_high + 1 == 0 { return vec![] };
+        let p_end = self.crk_col.crk_idx.upper_bound(&(x + 1)).unwrap_or(self.count);
+        if p_end == 0 { return 0..0 };
+        let mut p_high = p_end - 1;
 
         // Tighten
         while self.crk_col.crk[p_low] < x && p_low < p_high {
@@ -193,6 +213,9 @@ impl OverswapRLETable {
 
         while self.crk_col.crk[p_high] > x && p_high > p_low {
             let mut rl = self.crk_col.run_lengths[p_high];
+            if p_high < p_low + rl { // The run covers the piece, so nothing in it equals x.
+                return 0..0;
+            }
             if self.crk_col.crk[p_high - rl] == self.crk_col.crk[p_high] {
                 while self.crk_col.crk[p_high - rl] == self.crk_col.crk[p_high] {
                     let inc = self.crk_col.run_lengths[p_high - rl];
@@ -211,9 +234,9 @@ impl OverswapRLETable {
 
         if p_low == p_high {
             if self.crk_col.crk[p_low] == x {
-                return self.get_values(self.crk_col.base_idx[p_low..(p_low + 1)].iter(), col);
+                return p_low..(p_low + 1);
             } else {
-                return vec![];
+                return 0..0;
             }
         }
 
@@ -412,7 +435,7 @@ impl OverswapRLETable {
 
         // If nothing is selected, then return nothing
         if p_high < p_low {
-            return vec![];
+            return 0..0;
         }
 
         // Memo
@@ -422,7 +445,24 @@ impl OverswapRLETable {
         // Store in cracker index
         self.crk_col.crk_idx.insert(x, p_low);
         self.crk_col.crk_idx.insert(x + 1, p_high + 1);
-        self.get_values(self.crk_col.base_idx[p_low..(p_high + 1)].iter(), col)
+        p_low..(p_high + 1)
+    }
+
+    // Checks the invariants of the table and its cracker column, returning a description of the
+    // first violation found.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, c) in &self.columns {
+            if c.v.len() != self.count {
+                return Err(format!("column {} has {} values but the table has {} tuples", name, c.v.len(), self.count));
+            }
+        }
+        if self.crk_col.v.is_empty() {
+            // No cracker column has been set.
+            return Ok(());
+        }
+        self.crk_col.validate_alignment()?;
+        self.crk_col.validate_run_lengths()?;
+        self.crk_col.validate_crk_idx()
     }
 
     // Counts the places where a given column equals a given value
@@ -438,4 +478,14 @@ pub fn from_adjacency_vectors(src_node: Vec<i64>, dst_node: Vec<i64>, crk: &str)
     adjacency_list.insert(&mut map!{"src" => src_node, "dst" => dst_node});
     adjacency_list.set_crk_col(crk);
     adjacency_list
-}
\ No newline at end of file
+}
+
+// Returns an adjacency list with a weight for each edge, in column "weight". Cracking keeps it
+// aligned with src and dst through base_idx.
+pub fn from_weighted_adjacency_vectors(src_node: Vec<i64>, dst_node: Vec<i64>, weight: Vec<i64>, crk: &str) -> OverswapRLETable {
+    let mut adjacency_list = OverswapRLETable::new();
+    adjacency_list.new_columns(vec!["src", "dst", "weight"]);
+    adjacency_list.insert(&mut map!{"src" => src_node, "dst" => dst_node, "weight" => weight});
+    adjacency_list.set_crk_col(crk);
+    adjacency_list
+}
@@ -0,0 +1,343 @@
+// PageRank over Graph views
+// Global and personalised PageRank over any Graph, with vertex IDs of any kind. Vertices are
+// numbered densely once, in setup, so that rank vectors are indexed by position. Each iteration
+// pulls rank into every vertex from its in-neighbours, and gives back the rank of dangling
+// vertices, those without out-edges, which would otherwise be lost. run looks the in-neighbours up
+// once, through the dst cracked table, while the kernels of pagerank.rs and personrank.rs look
+// them up in their own storage every iteration.
+// A random surfer teleports, and leaves a dangling vertex, to a vertex drawn from the teleport
+// distribution: uniform for global PageRank, or user-supplied for personalised PageRank, such as
+// all on one person for their recommendations.
+// A PageRankConfig sets the damping factor, when to stop and the teleport distribution. It
+// iterates until the change in ranks, measured by the configured norm, falls below epsilon, or
+// for at most max_iterations iterations.
+// After a batch of edge changes, update corrects the previous ranks by forward push (Andersen,
+// Chung and Lang) instead of starting again. Only the sources of changed edges and the vertices
+// their residuals reach are looked up in the src cracked table.
+
+use time::{Duration, PreciseTime};
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use graph::Graph;
+use vertex_dictionary::VertexDictionary;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Norm {
+    // The sum of the absolute changes.
+    L1,
+    // The square root of the sum of the squared changes.
+    L2,
+    // The largest absolute change.
+    Max,
+}
+
+impl Norm {
+    fn of(self, a: &[f64], b: &[f64]) -> f64 {
+        self.size(a.iter().zip(b).map(|(x, y)| x - y))
+    }
+
+    fn size<I: Iterator<Item = f64>>(self, values: I) -> f64 {
+        let values = values.map(f64::abs);
+        match self {
+            Norm::L1 => values.sum(),
+            Norm::L2 => values.map(|c| c * c).sum::<f64>().sqrt(),
+            Norm::Max => values.fold(0.0, f64::max),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EdgeChange {
+    Insert(i64, i64),
+    Delete(i64, i64),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageRankConfig {
+    pub damping: f64,
+    pub epsilon: f64,
+    pub max_iterations: usize,
+    pub norm: Norm,
+    // Teleport weights by vertex, which needn't sum to 1. Vertices missing from it are never
+    // teleported to. If it's empty, teleports are uniform.
+    pub teleport: HashMap<i64, f64>,
+    // Vertices to rank besides those with edges, such as people without friends.
+    pub vertices: Vec<i64>,
+    // The residual a vertex needs for update to push it on. Unlike epsilon, which bounds the change
+    // in all the ranks, it applies to each vertex alone.
+    pub push_threshold: f64,
+}
+
+impl Default for PageRankConfig {
+    fn default() -> PageRankConfig {
+        PageRankConfig {
+            damping: 0.85,
+            epsilon: 1e-6,
+            max_iterations: 100,
+            norm: Norm::L1,
+            teleport: HashMap::new(),
+            vertices: Vec::new(),
+            push_threshold: 1e-9,
+        }
+    }
+}
+
+impl PageRankConfig {
+    pub fn new() -> PageRankConfig {
+        PageRankConfig::default()
+    }
+
+    // Teleports only to SOURCE, ranking vertices by their relevance to it.
+    pub fn personalise(&mut self, source: i64) {
+        self.teleport = HashMap::new();
+        self.teleport.insert(source, 1.0);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageRankResult {
+    pub ranks: HashMap<i64, f64>,
+    pub iterations: usize,
+    // The change in ranks at each iteration, by the configured norm.
+    pub residuals: Vec<f64>,
+    pub converged: bool,
+    // The time to build the graph, if it was built by build_and_run, and find out-degrees.
+    pub setup_time: Duration,
+    pub compute_time: Duration,
+}
+
+impl PageRankResult {
+    // The ranks of vertices 1 to n indexed by ID, with position 0 empty, as pagerank.rs used.
+    pub fn dense_ranks(&self, n: usize) -> Vec<f64> {
+        (0..=n as i64).map(|v| self.ranks.get(&v).cloned().unwrap_or(0.0)).collect()
+    }
+}
+
+// Builds a graph from an adjacency list and runs PageRank over it, counting the build as setup.
+pub fn build_and_run<G, F>(build: F, src: Vec<i64>, dst: Vec<i64>, config: &PageRankConfig) -> PageRankResult where G: Graph, F: FnOnce(Vec<i64>, Vec<i64>) -> G {
+    let start = PreciseTime::now();
+    let mut graph = build(src, dst);
+    let build_time = start.to(PreciseTime::now());
+    let mut result = run(&mut graph, config);
+    result.setup_time = result.setup_time + build_time;
+    result
+}
+
+pub fn run<G: Graph + ?Sized>(graph: &mut G, config: &PageRankConfig) -> PageRankResult {
+    let start = PreciseTime::now();
+    let dictionary = dictionary(graph.vertices().to_vec(), config);
+    // Each vertex's in-neighbours by position, looked up once.
+    let mut in_positions = Vec::with_capacity(dictionary.len());
+    let mut out_degree = Vec::with_capacity(dictionary.len());
+    for i in 1..=dictionary.len() as i64 {
+        let v = dictionary.external(i).unwrap();
+        in_positions.push(graph.in_neighbours(v).iter().map(|&w| dictionary.internal(w).unwrap() as usize - 1).collect::<Vec<usize>>());
+        out_degree.push(graph.out_degree(v));
+    }
+    iterate(&dictionary, &out_degree, config, start, |i, share| in_positions[i as usize - 1].iter().map(|&q| share[q]).sum())
+}
+
+// A dictionary over VERTICES and those CONFIG names, numbered in ascending order.
+fn dictionary(mut vertices: Vec<i64>, config: &PageRankConfig) -> VertexDictionary {
+    vertices.extend(config.vertices.iter().cloned());
+    vertices.extend(config.teleport.keys().cloned());
+    vertices.sort();
+    vertices.dedup();
+    VertexDictionary::from_vertices(&vertices)
+}
+
+// Renumbers the adjacency list SRC, DST densely from 1 over its vertices and those CONFIG names,
+// for the storage methods of pagerank.rs and personrank.rs.
+pub fn densify(src: &[i64], dst: &[i64], config: &PageRankConfig) -> (VertexDictionary, Vec<i64>, Vec<i64>) {
+    let mut dictionary = dictionary(src.iter().chain(dst).cloned().collect(), config);
+    let (src, dst) = dictionary.encode_edges(src, dst);
+    (dictionary, src, dst)
+}
+
+// The out-degree of each of vertices 1 to N of a dense adjacency list, at position id - 1.
+pub fn out_degrees(n: usize, src: &[i64]) -> Vec<usize> {
+    let mut out_degree = vec![0; n];
+    for &v in src {
+        out_degree[v as usize - 1] += 1;
+    }
+    out_degree
+}
+
+// The sum of SHARE over IN_NEIGHBOURS, by dense ID.
+pub fn inherited(in_neighbours: &[i64], share: &[f64]) -> f64 {
+    in_neighbours.iter().map(|&w| share[w as usize - 1]).sum()
+}
+
+// Iterates PageRank over the vertices of DICTIONARY, counting the time since START as setup.
+// INHERITED(i, share) sums share, each vertex's rank over its out-degree by position, over the
+// in-neighbours of the vertex with internal ID i, which each storage method looks up its own way.
+pub fn iterate<F>(dictionary: &VertexDictionary, out_degree: &[usize], config: &PageRankConfig, start: PreciseTime, mut inherited: F) -> PageRankResult where F: FnMut(i64, &[f64]) -> f64 {
+    if !(0.0..=1.0).contains(&config.damping) {
+        panic!("iterate: Damping factor {} is not between 0 and 1", config.damping);
+    }
+    let n = dictionary.len();
+    let vertices = dictionary.decode(&(1..=n as i64).collect::<Vec<i64>>());
+    let teleport = teleport_distribution(&vertices, &config.teleport);
+    let setup_end = PreciseTime::now();
+
+    let d = config.damping;
+    let mut rank = teleport.clone();
+    let mut residuals = Vec::new();
+    let mut converged = false;
+    while residuals.len() < config.max_iterations {
+        let dangling: f64 = (0..n).filter(|&p| out_degree[p] == 0).map(|p| rank[p]).sum();
+        let share: Vec<f64> = (0..n).map(|p| if out_degree[p] == 0 { 0.0 } else { rank[p] / out_degree[p] as f64 }).collect();
+        let mut new_rank = vec![0.0; n];
+        for p in 0..n {
+            new_rank[p] = (1.0 - d) * teleport[p] + d * (inherited(p as i64 + 1, &share) + dangling * teleport[p]);
+        }
+        residuals.push(config.norm.of(&new_rank, &rank));
+        rank = new_rank;
+        if residuals[residuals.len() - 1] < config.epsilon {
+            converged = true;
+            break;
+        }
+    }
+
+    PageRankResult {
+        ranks: vertices.into_iter().zip(rank).collect(),
+        iterations: residuals.len(),
+        residuals,
+        converged,
+        setup_time: start.to(setup_end),
+        compute_time: setup_end.to(PreciseTime::now()),
+    }
+}
+
+// The teleport probability of each of VERTICES from WEIGHTS, or uniform if there are none.
+fn teleport_distribution(vertices: &[i64], weights: &HashMap<i64, f64>) -> Vec<f64> {
+    if weights.is_empty() {
+        return vec![(vertices.len() as f64).recip(); vertices.len()];
+    }
+    if let Some((v, w)) = weights.iter().find(|e| e.1.is_nan() || *e.1 < 0.0) {
+        panic!("teleport_distribution: Vertex {} has teleport weight {}", v, w);
+    }
+    let total: f64 = weights.values().sum();
+    if total <= 0.0 {
+        panic!("teleport_distribution: Teleport weights sum to {}", total);
+    }
+    vertices.iter().map(|v| weights.get(v).map_or(0.0, |w| w / total)).collect()
+}
+
+// Corrects PREVIOUS, the ranks from CONFIG before CHANGES, for GRAPH, which holds the edges after
+// them. Cracked tables can't yet take updates once cracked, so GRAPH has to be built afresh, but
+// only the vertices affected by the changes are looked up.
+// The changes leave residuals at the targets of the changed sources' edges, which are pushed along
+// out-edges until none exceeds config.push_threshold, for at most config.max_iterations rounds of
+// pushes. Each round pushes its vertices in ascending order, so the result doesn't depend on the
+// order of a HashMap.
+// The residuals record the norm of what's left after each round. Rank pushed from a dangling vertex
+// would spread over every vertex teleported to, so it's summed instead: teleporting t adds
+// t / (1 - d) of the new ranks, and the ranks are scaled up to match at the end.
+// Vertices which lose all their edges are still ranked. With uniform teleports, vertices new to
+// the graph must be in PREVIOUS already, such as by ranking them through config.vertices.
+pub fn update<G: Graph + ?Sized>(graph: &mut G, previous: &HashMap<i64, f64>, changes: &[EdgeChange], config: &PageRankConfig) -> PageRankResult {
+    if !(0.0..1.0).contains(&config.damping) {
+        panic!("update: Damping factor {} is not between 0 and 1, exclusive", config.damping);
+    }
+    let start = PreciseTime::now();
+    let d = config.damping;
+    let mut vertices: HashSet<i64> = previous.keys().cloned().collect();
+    vertices.extend(graph.vertices().iter().cloned());
+    vertices.extend(config.vertices.iter().cloned());
+    vertices.extend(config.teleport.keys().cloned());
+    // A new vertex would change a uniform teleport distribution, and with it every dangling vertex's
+    // share of rank, so it has to have been ranked before.
+    if config.teleport.is_empty() {
+        if let Some(v) = vertices.iter().find(|v| !previous.contains_key(v)) {
+            panic!("update: Vertex {} has no previous rank, which uniform teleports need", v);
+        }
+    }
+    let mut rank: HashMap<i64, f64> = vertices.iter().map(|&v| (v, previous.get(&v).cloned().unwrap_or(0.0))).collect();
+
+    let mut residual: BTreeMap<i64, f64> = BTreeMap::new();
+    // The rank pushed to the teleport distribution, from dangling vertices.
+    let mut teleported = 0.0;
+    // The change in each changed source's out-edges, by target.
+    let mut changed: BTreeMap<i64, BTreeMap<i64, i64>> = BTreeMap::new();
+    for change in changes {
+        let (u, v, c) = match *change {
+            EdgeChange::Insert(u, v) => (u, v, 1),
+            EdgeChange::Delete(u, v) => (u, v, -1),
+        };
+        *changed.entry(u).or_default().entry(v).or_insert(0) += c;
+    }
+    for (u, change) in changed {
+        let mut after: BTreeMap<i64, i64> = BTreeMap::new();
+        for v in graph.out_neighbours(u) {
+            *after.entry(v).or_insert(0) += 1;
+        }
+        let mut before = after.clone();
+        for (&v, &c) in &change {
+            let count = before.entry(v).or_insert(0);
+            *count -= c;
+            if *count < 0 {
+                panic!("update: Edge {} -> {} was inserted but isn't in the graph", u, v);
+            }
+        }
+        before.retain(|_, &mut count| count > 0);
+        // Replace U's old share of rank along its out-edges with its new share.
+        let ru = rank[&u];
+        for (edges, sign) in [(after, 1.0), (before, -1.0)] {
+            let degree: i64 = edges.values().sum();
+            if degree == 0 {
+                teleported += sign * d * ru;
+            }
+            for (v, count) in edges {
+                *residual.entry(v).or_insert(0.0) += sign * d * ru * count as f64 / degree as f64;
+            }
+        }
+    }
+    let setup_end = PreciseTime::now();
+
+    let mut residuals = Vec::new();
+    let mut converged = false;
+    let mut round: Vec<i64> = residual.iter().filter(|e| e.1.abs() > config.push_threshold).map(|e| *e.0).collect();
+    loop {
+        if round.is_empty() {
+            converged = true;
+            break;
+        }
+        if residuals.len() == config.max_iterations {
+            break;
+        }
+        let mut next: BTreeSet<i64> = BTreeSet::new();
+        for u in round {
+            let r = residual.insert(u, 0.0).unwrap_or(0.0);
+            *rank.get_mut(&u).unwrap() += r;
+            let neighbours = graph.out_neighbours(u);
+            if neighbours.is_empty() {
+                teleported += d * r;
+            }
+            for &v in &neighbours {
+                let rv = residual.entry(v).or_insert(0.0);
+                *rv += d * r / neighbours.len() as f64;
+                if rv.abs() > config.push_threshold {
+                    next.insert(v);
+                }
+            }
+        }
+        residuals.push(config.norm.size(residual.values().cloned()));
+        round = next.into_iter().collect();
+    }
+
+    // The teleported rank adds teleported / (1 - d) of the new ranks.
+    let share = 1.0 - teleported / (1.0 - d);
+    for r in rank.values_mut() {
+        *r /= share;
+    }
+    PageRankResult {
+        ranks: rank,
+        iterations: residuals.len(),
+        residuals,
+        converged,
+        setup_time: start.to(setup_end),
+        compute_time: setup_end.to(PreciseTime::now()),
+    }
+}
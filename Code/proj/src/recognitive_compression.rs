@@ -8,6 +8,7 @@ use column::Column;
 use column::IntCol;
 
 use std::collections::HashMap;
+use std::ops::Range;
 use std::slice::Iter;
 
 #[derive(Clone)]
@@ -176,34 +177,56 @@ impl ReCoTable {
 
     // Returns the elements of T where the cracker columns's value equals X
     pub fn cracker_select_specific(&mut self, x: i64, col: &str) -> Vec<i64> {
+        let piece = self.crack_select_specific(x);
+        #[cfg(feature = "validate")]
+        self.validate().unwrap_or_else(|e| panic!("cracker_select_specific({}): {}", x, e));
+        self.get_values(self.crk_col.base_idx[piece].iter(), col)
+    }
+
+    // Returns the elements of each of COLS where the cracker column's value equals X. They are
+    // all read from the same piece, so the ith elements of each belong to the same tuple.
+    pub fn cracker_select_columns(&mut self, x: i64, cols: &[&str]) -> Vec<Vec<i64>> {
+        let piece = self.crack_select_specific(x);
+        #[cfg(feature = "validate")]
+        self.validate().unwrap_or_else(|e| panic!("cracker_select_columns({}): {}", x, e));
+        cols.iter().map(|col| self.get_values(self.crk_col.base_idx[piece.clone()].iter(), col)).collect()
+    }
+
+    // Cracks on X and returns the positions in base_idx of the tuples where the cracker column's
+    // value equals X.
+    fn crack_select_specific(&mut self, x: i64) -> Range<usize> {
         // Start with a pointer at both ends of the piece: p_low, p_high
         let mut p_low =  self.crk_col.crk_idx.lower_bound(&x).unwrap_or(0);
-        let mut p_high = self.crk_col.crk_idx.upper_bound(&(x + 1)).unwrap_or(self.count) - 1;
-        if p_high + 1 == 0 { return vec![] };
+        if p_low == self.count {
+            return 0..0;
+        }
+        let p_end = self.crk_col.crk_idx.upper_bound(&(x + 1)).unwrap_or(self.count);
+        if p_end == 0 { return 0..0 };
+        let mut p_high = p_end - 1;
 
         let is_uniform_column_piece = self.crk_col.crk_idx.contains(x) && self.crk_col.crk_idx.contains(x + 1);
         if is_uniform_column_piece {
-            return self.get_values(self.crk_col.base_idx[p_low..(p_high + 1)].iter(), col);
+            return p_low..(p_high + 1);
         }
 
         // while p_low is pointing at an element satisfying c_low,  move it forwards
         while self.crk_col.crk[p_low] < x {
             p_low += 1;
             if p_low == self.count as usize {
-                return vec![]
+                return 0..0
             }
         }
 
         // while p_high is pointing at an element satisfying c_high, move it backwards
         while self.crk_col.crk[p_high] > x {
             if p_high == 0 {
-                return vec![];
+                return 0..0;
             }
             p_high -= 1;
         }
 
         if p_low == p_high {
-            return self.get_values(self.crk_col.base_idx[p_low..(p_high + 1)].iter(), col);
+            return p_low..(p_high + 1);
         }
 
         let mut p_itr = p_low.clone();
@@ -231,7 +254,28 @@ impl ReCoTable {
 
         self.crk_col.crk_idx.insert(x, p_low);
         self.crk_col.crk_idx.insert(x + 1, p_high + 1);
-        self.get_values(self.crk_col.base_idx[p_low..(p_high + 1)].iter(), col)
+        p_low..(p_high + 1)
+    }
+
+    // Checks the invariants of the table and its cracker column, returning a description of the
+    // first violation found.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, c) in &self.int_columns {
+            if c.v.len() != self.count {
+                return Err(format!("column {} has {} values but the table has {} tuples", name, c.v.len(), self.count));
+            }
+        }
+        if self.crk_col.v.is_empty() {
+            // No cracker column has been set.
+            return Ok(());
+        }
+        match self.int_columns.get(&self.crk_col_name) {
+            Some(c) if c.v == self.crk_col.v => {},
+            Some(_) => return Err(format!("crk_col.v differs from column {}", self.crk_col_name)),
+            None => return Err(format!("crk_col_name {} is not a column", self.crk_col_name)),
+        }
+        self.crk_col.validate_alignment()?;
+        self.crk_col.validate_crk_idx()
     }
 
     // Counts the places where a given column equals a given value
@@ -247,4 +291,14 @@ pub fn from_adjacency_vectors(src_node: Vec<i64>, dst_node: Vec<i64>, crk: &str)
     adjacency_list.insert(&mut map!{"src" => src_node, "dst" => dst_node});
     adjacency_list.set_crk_col(crk);
     adjacency_list
-}
\ No newline at end of file
+}
+
+// Returns an adjacency list with a weight for each edge, in column "weight". Cracking keeps it
+// aligned with src and dst through base_idx.
+pub fn from_weighted_adjacency_vectors(src_node: Vec<i64>, dst_node: Vec<i64>, weight: Vec<i64>, crk: &str) -> ReCoTable {
+    let mut adjacency_list = ReCoTable::new();
+    adjacency_list.new_columns(vec!["src", "dst", "weight"]);
+    adjacency_list.insert(&mut map!{"src" => src_node, "dst" => dst_node, "weight" => weight});
+    adjacency_list.set_crk_col(crk);
+    adjacency_list
+}
@@ -0,0 +1,148 @@
+// Single-source shortest paths
+// Weighted distances over any WeightedGraph, so they run directly against a cracked table with a
+// weight column. Dijkstra's algorithm settles vertices one at a time from a priority queue.
+// Delta-stepping (Meyer and Sanders) settles them in buckets of width delta, relaxing the light
+// edges of a bucket, those no heavier than delta, until it empties, then its heavy edges once.
+// Weights must not be negative.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+
+use graph::WeightedGraph;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShortestPaths {
+    pub source: i64,
+    distances: HashMap<i64, f64>,
+    parents: HashMap<i64, i64>,
+}
+
+impl ShortestPaths {
+    fn new(source: i64) -> ShortestPaths {
+        let mut distances = HashMap::new();
+        distances.insert(source, 0.0);
+        ShortestPaths { source, distances, parents: HashMap::new() }
+    }
+
+    // Lowers the distance to V through an edge from U, returning whether it did.
+    fn relax(&mut self, u: i64, v: i64, distance: f64) -> bool {
+        if self.distances.get(&v).is_none_or(|&d| distance < d) {
+            self.distances.insert(v, distance);
+            self.parents.insert(v, u);
+            true
+        } else {
+            false
+        }
+    }
+
+    // The distance from the source to V, or None if V can't be reached.
+    pub fn distance(&self, v: i64) -> Option<f64> {
+        self.distances.get(&v).cloned()
+    }
+
+    pub fn distances(&self) -> &HashMap<i64, f64> {
+        &self.distances
+    }
+
+    // A shortest path from the source to V.
+    pub fn path_to(&self, v: i64) -> Option<Vec<i64>> {
+        if !self.distances.contains_key(&v) {
+            return None;
+        }
+        let mut path = vec![v];
+        let mut current = v;
+        while let Some(&parent) = self.parents.get(&current) {
+            path.push(parent);
+            current = parent;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+fn check_weight(function: &str, u: i64, v: i64, w: f64) {
+    if w.is_nan() || w < 0.0 {
+        panic!("{}: Edge {} -> {} has weight {}", function, u, v, w);
+    }
+}
+
+// A vertex queued at a distance, ordered so that BinaryHeap pops the nearest first.
+#[derive(PartialEq)]
+struct Queued(f64, i64);
+
+impl Eq for Queued {}
+
+impl Ord for Queued {
+    fn cmp(&self, other: &Queued) -> Ordering {
+        other.0.partial_cmp(&self.0).unwrap_or(Ordering::Equal).then(other.1.cmp(&self.1))
+    }
+}
+
+impl PartialOrd for Queued {
+    fn partial_cmp(&self, other: &Queued) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+pub fn dijkstra<G: WeightedGraph + ?Sized>(graph: &mut G, source: i64) -> ShortestPaths {
+    let mut paths = ShortestPaths::new(source);
+    let mut queue = BinaryHeap::new();
+    queue.push(Queued(0.0, source));
+    while let Some(Queued(d, u)) = queue.pop() {
+        // Skip entries left behind by a later relaxation.
+        if d > paths.distances[&u] {
+            continue;
+        }
+        for (v, w) in graph.out_edges(u) {
+            check_weight("dijkstra", u, v, w);
+            if paths.relax(u, v, d + w) {
+                queue.push(Queued(d + w, v));
+            }
+        }
+    }
+    paths
+}
+
+pub fn delta_stepping<G: WeightedGraph + ?Sized>(graph: &mut G, source: i64, delta: f64) -> ShortestPaths {
+    if delta.is_nan() || delta <= 0.0 {
+        panic!("delta_stepping: Delta {} is not positive", delta);
+    }
+    let mut paths = ShortestPaths::new(source);
+    // Only buckets holding vertices are kept, so a long edge doesn't add every bucket before it.
+    let mut buckets: BTreeMap<usize, Vec<i64>> = BTreeMap::new();
+    buckets.insert(0, vec![source]);
+    let bucket_of = |d: f64| (d / delta) as usize;
+    while let Some(i) = buckets.keys().next().copied() {
+        // The heavy edges of each vertex settled in this bucket, relaxed once it's empty.
+        let mut settled: HashMap<i64, Vec<(i64, f64)>> = HashMap::new();
+        while let Some(u) = buckets.get_mut(&i).and_then(Vec::pop) {
+            let d = paths.distances[&u];
+            // Skip vertices which have moved to an earlier bucket, or which were settled already.
+            if bucket_of(d) != i || settled.contains_key(&u) {
+                continue;
+            }
+            let mut heavy = Vec::new();
+            for (v, w) in graph.out_edges(u) {
+                check_weight("delta_stepping", u, v, w);
+                if w > delta {
+                    heavy.push((v, w));
+                } else if paths.relax(u, v, d + w) {
+                    buckets.entry(bucket_of(d + w)).or_default().push(v);
+                    // A shorter path to a settled vertex unsettles it.
+                    settled.remove(&v);
+                }
+            }
+            settled.insert(u, heavy);
+        }
+        buckets.remove(&i);
+        for (u, heavy) in settled {
+            let d = paths.distances[&u];
+            for (v, w) in heavy {
+                if paths.relax(u, v, d + w) {
+                    buckets.entry(bucket_of(d + w)).or_default().push(v);
+                }
+            }
+        }
+    }
+    paths
+}
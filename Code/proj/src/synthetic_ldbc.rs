@@ -0,0 +1,212 @@
+// Synthetic LDBC-like social networks
+// Generates person and knows data with the schema of LDBC's person_0_0.csv and
+// person_knows_person_0_0.csv, so that the PersonRank benchmarks can run without the LDBC files.
+// It imitates the properties of LDBC data which matter to the benchmarks:
+//   - Sizes: the number of people and friendships roughly follows what LDBC datagen produces at
+//     the same scale factor.
+//   - Degrees: each person's degree is drawn from a skewed (lognormal) distribution, and as in
+//     LDBC datagen, friendships are mostly made between people near each other in two orderings,
+//     standing in for shared universities and interests, with the rest made at random.
+//   - IDs: people are numbered sparsely in order of creation, and the time they were created goes
+//     into the high bits of their ID, which leaves large gaps between the IDs of different periods.
+
+use rand::Rng;
+use rand::distributions::{IndependentSample, LogNormal};
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use datagen;
+use datasets;
+use load_ldbc;
+
+// (scale factor, people, friendships) produced by LDBC datagen. Other scale factors are
+// interpolated between these on a log-log scale.
+const LDBC_SIZES: [(f64, f64, f64); 4] = [
+    (0.1, 1528.0, 14073.0),
+    (1.0, 9892.0, 180623.0),
+    (10.0, 65645.0, 1938516.0),
+    (100.0, 448626.0, 19941198.0),
+];
+
+// The simulation runs over these three years, as LDBC's does.
+const START: i64 = 1262304000000; // 2010-01-01
+const END: i64 = 1356998400000; // 2013-01-01
+const ID_PERIODS: i64 = 12;
+const ID_PERIOD_SHIFT: u32 = 41;
+
+// People only look this far along each ordering for friends.
+const WINDOW: usize = 250;
+
+const FIRST_NAMES: [&str; 16] = ["Mahinda", "Carmen", "Baruch", "Jan", "Hossein", "Ali", "Chen", "Maria", "Ivan", "Ana", "Rahul", "Yang", "Eva", "Jose", "Otto", "Aiko"];
+const LAST_NAMES: [&str; 16] = ["Perera", "Lepland", "Dego", "Zakrzewski", "Forouhar", "Achiou", "Wang", "Garcia", "Petrov", "Silva", "Sharma", "Li", "Novak", "Lopez", "Becker", "Sato"];
+const BROWSERS: [(&str, f64); 5] = [("Firefox", 0.45), ("Chrome", 0.35), ("Internet Explorer", 0.12), ("Safari", 0.05), ("Opera", 0.03)];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyntheticPerson {
+    pub id: i64,
+    pub first_name: String,
+    pub last_name: String,
+    pub gender: String,
+    // Milliseconds since the Unix epoch.
+    pub birthday: i64,
+    pub creation_date: i64,
+    pub location_ip: String,
+    pub browser_used: String,
+}
+
+// People, and friendships from src to dst, where the person in src was created first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SocialNetwork {
+    pub people: Vec<SyntheticPerson>,
+    pub src: Vec<i64>,
+    pub dst: Vec<i64>,
+    pub creation_dates: Vec<i64>,
+}
+
+// The approximate number of people and friendships LDBC datagen makes at a scale factor.
+pub fn ldbc_size(scale_factor: f64) -> (usize, usize) {
+    if scale_factor <= 0.0 {
+        panic!("ldbc_size: Scale factor {} is not positive", scale_factor);
+    }
+    let k = (0..LDBC_SIZES.len() - 2).find(|&k| scale_factor < LDBC_SIZES[k + 1].0).unwrap_or(LDBC_SIZES.len() - 2);
+    let (sf_0, people_0, knows_0) = LDBC_SIZES[k];
+    let (sf_1, people_1, knows_1) = LDBC_SIZES[k + 1];
+    let t = (scale_factor.ln() - sf_0.ln()) / (sf_1.ln() - sf_0.ln());
+    let interpolate = |a: f64, b: f64| (a.ln() + t * (b.ln() - a.ln())).exp().round() as usize;
+    (interpolate(people_0, people_1).max(2), interpolate(knows_0, knows_1).max(1))
+}
+
+pub fn generate(scale_factor: f64, seed: usize) -> SocialNetwork {
+    let (n, knows) = ldbc_size(scale_factor);
+    let mut rng = datagen::seeded_rng(seed);
+    let people = generate_people(n, &mut rng);
+
+    // Each person wants a degree drawn from a lognormal distribution with the mean degree of LDBC
+    // data at this scale.
+    let mean = 2.0 * knows as f64 / n as f64;
+    let sigma = 1.0;
+    let degrees = LogNormal::new(mean.ln() - sigma * sigma / 2.0, sigma);
+    let targets: Vec<usize> = (0..n).map(|_| (degrees.ind_sample(&mut rng).round() as usize).max(1).min(n - 1)).collect();
+
+    let mut friends = Friends { targets, degrees: vec![0; n], edges: HashSet::new(), pairs: Vec::new() };
+    for &share in &[0.45, 0.45] {
+        let mut order: Vec<usize> = (0..n).collect();
+        rng.shuffle(&mut order);
+        for p in 0..n {
+            let i = order[p];
+            let quota = friends.degrees[i] + (share * friends.targets[i] as f64).ceil() as usize;
+            for step in 1..WINDOW.min(n - p) {
+                if friends.degrees[i] >= quota.min(friends.targets[i]) {
+                    break;
+                }
+                // Nearer people are more likely to be friends.
+                if rng.gen::<f64>() < 0.98f64.powi(step as i32) {
+                    friends.connect(i, order[p + step]);
+                }
+            }
+        }
+    }
+    // The rest of each person's friendships are random.
+    for i in 0..n {
+        let mut attempts = 0;
+        while friends.degrees[i] < friends.targets[i] && attempts < 4 * friends.targets[i] {
+            friends.connect(i, rng.gen_range(0, n));
+            attempts += 1;
+        }
+    }
+
+    let mut network = SocialNetwork { people, src: Vec::new(), dst: Vec::new(), creation_dates: Vec::new() };
+    for (i, j) in friends.pairs {
+        let (a, b) = (&network.people[i], &network.people[j]);
+        let (first, second) = if a.creation_date <= b.creation_date { (a, b) } else { (b, a) };
+        network.src.push(first.id);
+        network.dst.push(second.id);
+        network.creation_dates.push(second.creation_date + rng.gen_range(0, (END - second.creation_date).max(1)));
+    }
+    network
+}
+
+struct Friends {
+    targets: Vec<usize>,
+    degrees: Vec<usize>,
+    edges: HashSet<(usize, usize)>,
+    pairs: Vec<(usize, usize)>,
+}
+
+impl Friends {
+    fn connect(&mut self, i: usize, j: usize) {
+        if i == j || self.degrees[j] >= self.targets[j] || !self.edges.insert((i.min(j), i.max(j))) {
+            return;
+        }
+        self.degrees[i] += 1;
+        self.degrees[j] += 1;
+        self.pairs.push((i, j));
+    }
+}
+
+// Makes N people, in order of creation.
+fn generate_people<R: Rng>(n: usize, rng: &mut R) -> Vec<SyntheticPerson> {
+    let mut creation_dates: Vec<i64> = (0..n).map(|_| rng.gen_range(START, END)).collect();
+    creation_dates.sort();
+
+    let mut local_id = 0;
+    creation_dates.into_iter().map(|creation_date| {
+        local_id += rng.gen_range(1, 8);
+        let period = (creation_date - START) * ID_PERIODS / (END - START);
+        let browser = rng.gen::<f64>();
+        let mut cumulative = 0.0;
+        let browser_used = BROWSERS.iter().find(|b| { cumulative += b.1; browser < cumulative }).unwrap_or(&BROWSERS[0]).0;
+        SyntheticPerson {
+            id: (period << ID_PERIOD_SHIFT) | local_id,
+            first_name: rng.choose(&FIRST_NAMES).unwrap().to_string(),
+            last_name: rng.choose(&LAST_NAMES).unwrap().to_string(),
+            gender: if rng.gen() { "male" } else { "female" }.to_string(),
+            // Born between 1980 and 1990.
+            birthday: 315532800000 + rng.gen_range(0, 3652) * 86_400_000,
+            creation_date,
+            location_ip: format!("{}.{}.{}.{}", rng.gen_range(1, 224), rng.gen_range(0, 256), rng.gen_range(0, 256), rng.gen_range(1, 255)),
+            browser_used: browser_used.to_string(),
+        }
+    }).collect()
+}
+
+impl SocialNetwork {
+    pub fn ids(&self) -> Vec<i64> {
+        self.people.iter().map(|p| p.id).collect()
+    }
+
+    // Writes person_0_0.csv and person_knows_person_0_0.csv into DIR, creating it if needed, so
+    // that the network can be loaded like LDBC data.
+    pub fn write<P: AsRef<Path>>(&self, dir: P) -> Result<(), String> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+
+        let path = dir.join(datasets::NODES_FILE);
+        let write_people = || -> Result<(), io::Error> {
+            let mut out = BufWriter::new(fs::File::create(&path)?);
+            writeln!(out, "id|firstName|lastName|gender|birthday|creationDate|locationIP|browserUsed")?;
+            for p in &self.people {
+                writeln!(out, "{}|{}|{}|{}|{}|{}|{}|{}", p.id, p.first_name, p.last_name, p.gender,
+                         load_ldbc::format_timestamp(p.birthday, true), load_ldbc::format_timestamp(p.creation_date, false),
+                         p.location_ip, p.browser_used)?;
+            }
+            out.flush()
+        };
+        write_people().map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        let path = dir.join(datasets::EDGES_FILE);
+        let write_knows = || -> Result<(), io::Error> {
+            let mut out = BufWriter::new(fs::File::create(&path)?);
+            writeln!(out, "Person.id|Person.id|creationDate")?;
+            for i in 0..self.src.len() {
+                writeln!(out, "{}|{}|{}", self.src[i], self.dst[i], load_ldbc::format_timestamp(self.creation_dates[i], false))?;
+            }
+            out.flush()
+        };
+        write_knows().map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
@@ -1,8 +1,41 @@
 use bit_vec::BitVec;
+use quickcheck::{Arbitrary, Gen, QuickCheck, StdGen, TestResult};
+use rand::thread_rng;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
 
+use decomposed_cracking;
+use recognitive_compression;
+use compactive_compression;
+use underswap_rle_compression;
 use overswap_rle_compression;
+use datagen;
+use datasets;
+use differential;
+use load_graph;
+use load_ldbc;
+use synthetic_ldbc;
+use vertex_dictionary;
+use vertex_dictionary::VertexDictionary;
+use bfs;
+use export;
+use export::Export;
+use graph;
+use graph::Graph;
+use traversal;
+use sssp;
+use components;
+use centrality;
+use triangles;
+use ranking;
+use pagerank;
+use personrank;
+use workload;
+use workload::{Pattern, Query};
 
 fn discover(dst: i64, visited: &mut BitVec, frontier: &mut Vec<i64>) {
     if !visited.get((dst as usize) - 1).unwrap_or(false) && !frontier.contains(&dst) {
@@ -127,4 +160,1682 @@ fn l_side_i_longer_tessellating() {
     let dst = vec![2, 13, 10, 16, 2, 20, 20, 7, 19, 27, 25, 1, 2, 3, 22, 2, 2, 2, 29, 2, 5, 2, 29, 20, 22, 22, 7, 3, 20, 21, 2, 30, 17, 16, 11, 12, 9, 23, 4, 13, 20, 26, 9, 8, 3, 22, 2, 15, 24, 29, 2, 28, 6, 18, 2, 14, 2, 29];
     let start_node = 1;
     assert!(bfs_test(30, src, dst, start_node));
-}
\ No newline at end of file
+}
+
+// Selects each vertex's neighbours from every table type in turn, validating the tables after
+// every query.
+fn validate_each_query(src: Vec<i64>, dst: Vec<i64>, queries: Vec<i64>) {
+    let mut decracked = decomposed_cracking::from_adjacency_vectors(src.clone(), dst.clone(), "src");
+    let mut reco = recognitive_compression::from_adjacency_vectors(src.clone(), dst.clone(), "src");
+    let mut coco = compactive_compression::from_adjacency_vectors(src.clone(), dst.clone(), "src");
+    let mut underswap = underswap_rle_compression::from_adjacency_vectors(src.clone(), dst.clone(), "src");
+    let mut overswap = overswap_rle_compression::from_adjacency_vectors(src.clone(), dst.clone(), "src");
+
+    for x in queries {
+        decracked.cracker_select_specific(x, "dst");
+        assert_eq!(decracked.validate(), Ok(()), "Decracked after selecting {}", x);
+        reco.cracker_select_specific(x, "dst");
+        assert_eq!(reco.validate(), Ok(()), "Reco after selecting {}", x);
+        coco.cracker_select_specific(x, "dst");
+        assert_eq!(coco.validate(), Ok(()), "Coco after selecting {}", x);
+        underswap.cracker_select_specific(x, "dst");
+        assert_eq!(underswap.validate(), Ok(()), "Underswap after selecting {}", x);
+        overswap.cracker_select_specific(x, "dst");
+        assert_eq!(overswap.validate(), Ok(()), "Overswap after selecting {}", x);
+    }
+}
+
+#[test]
+fn tables_stay_valid() {
+    let src = vec![4, 16, 22, 8, 26, 13, 22, 4, 18, 12, 13, 22, 10, 14, 22, 8, 19, 29, 8, 8, 17, 18, 22, 5, 2, 28, 8, 12, 24, 13, 15, 21, 30, 6, 18, 25, 7, 9, 19, 19, 4, 3, 11, 17, 28, 10, 8, 28, 22, 11, 29, 1, 20, 30, 8, 23, 4, 27];
+    let dst = vec![20, 18, 19, 6, 12, 22, 2, 11, 16, 26, 27, 25, 9, 4, 13, 30, 18, 30, 28, 13, 24, 19, 7, 19, 22, 8, 17, 29, 17, 8, 18, 4, 29, 8, 15, 22, 22, 10, 5, 22, 14, 8, 28, 8, 11, 22, 23, 1, 10, 4, 12, 28, 4, 8, 3, 8, 21, 13];
+    validate_each_query(src, dst, vec![7, 22, 2, 8, 30, 1, 13, 17, 22, 4, 19, 12, 28, 5, 16]);
+}
+
+#[test]
+fn tables_stay_valid_on_repeated_queries() {
+    let src = vec![22, 14, 5, 7, 11, 6, 9, 17, 28, 28, 16, 21, 5, 3, 5, 27, 10, 30, 20, 4, 28, 5, 8, 3, 3, 11, 13, 5, 6, 9, 25, 8, 2, 3, 18, 24, 29, 17, 15, 25, 28, 3, 3, 3, 12, 3, 11, 11, 11, 19, 5, 26, 1, 5, 6, 28, 23, 6];
+    let dst = vec![5, 5, 22, 3, 16, 21, 28, 29, 9, 27, 11, 6, 28, 13, 14, 28, 3, 11, 8, 3, 11, 2, 3, 24, 12, 30, 3, 26, 5, 18, 5, 20, 5, 11, 9, 3, 17, 25, 6, 17, 23, 7, 8, 10, 3, 4, 28, 3, 19, 11, 25, 5, 6, 6, 15, 5, 28, 1];
+    validate_each_query(src, dst, vec![12, 3, 3, 5, 28, 11, 12, 1, 30, 5, 3, 6]);
+}
+
+#[test]
+fn validate_reports_broken_run_lengths() {
+    let mut overswap = overswap_rle_compression::from_adjacency_vectors(vec![1, 2, 2, 3], vec![2, 1, 3, 2], "src");
+    overswap.cracker_select_specific(2, "dst");
+    assert_eq!(overswap.validate(), Ok(()));
+    overswap.crk_col.run_lengths[1] = 3;
+    assert!(overswap.validate().is_err());
+}
+
+#[test]
+fn validate_reports_misplaced_crk_idx_entry() {
+    let mut decracked = decomposed_cracking::from_adjacency_vectors(vec![3, 1, 2, 1], vec![1, 2, 3, 3], "src");
+    decracked.cracker_select_specific(2, "dst");
+    assert_eq!(decracked.validate(), Ok(()));
+    decracked.crk_col.crk_idx.insert(3, 0);
+    assert!(decracked.validate().is_err());
+}
+
+// == DIFFERENTIAL TESTS ==
+
+// A column of values to crack and a sequence of values to select from it, in order.
+#[derive(Clone, Debug)]
+struct Workload {
+    column: Vec<i64>,
+    queries: Vec<i64>,
+}
+
+impl Arbitrary for Workload {
+    fn arbitrary<G: Gen>(g: &mut G) -> Workload {
+        // Draw values from a small domain so that the column has plenty of duplicates.
+        let size = g.size();
+        let n = g.gen_range(1, size + 2);
+        let domain = g.gen_range(1, size as i64 + 2);
+        let column: Vec<i64> = (0..n).map(|_| g.gen_range(0, domain)).collect();
+        let n_queries = g.gen_range(1, size + 2);
+        let queries = (0..n_queries).map(|_| {
+            if g.gen_weighted_bool(4) { g.gen_range(-1, domain + 2) } else { column[g.gen_range(0, n)] }
+        }).collect();
+        Workload { column, queries }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item=Workload>> {
+        let shrunk = (self.column.clone(), self.queries.clone()).shrink()
+            .filter(|(column, _)| !column.is_empty())
+            .map(|(column, queries)| Workload { column, queries });
+        Box::new(shrunk)
+    }
+}
+
+fn select_specific_matches_scan(w: Workload) -> TestResult {
+    match differential::check_select_specific(&w.column, &w.queries) {
+        Ok(()) => TestResult::passed(),
+        Err(e) => TestResult::error(e),
+    }
+}
+
+#[test]
+fn select_specific_matches_scan_on_small_workloads() {
+    QuickCheck::new().tests(2000).gen(StdGen::new(thread_rng(), 10)).quickcheck(select_specific_matches_scan as fn(Workload) -> TestResult);
+}
+
+#[test]
+fn select_specific_matches_scan_on_random_workloads() {
+    QuickCheck::new().tests(500).quickcheck(select_specific_matches_scan as fn(Workload) -> TestResult);
+}
+
+// Minimal reproductions of failures the differential tests have found.
+#[test]
+fn select_specific_regressions() {
+    let workloads = vec![
+        (vec![0, 0], vec![0, 1]),
+        (vec![0, 0], vec![0, -1]),
+        (vec![7, 6, 6], vec![0, 7, 0]),
+        (vec![29, 28, 28, 0, 29], vec![27, 29]),
+    ];
+    for (column, queries) in workloads {
+        let w = Workload { column, queries };
+        assert!(!select_specific_matches_scan(w.clone()).is_failure(), "{:?}", w);
+    }
+}
+
+// The checked-in seeds of the cracker_select_specific fuzz target, from fuzz/seed_corpus.rs.
+#[test]
+fn select_specific_matches_scan_on_the_fuzz_corpus() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fuzz/corpus/cracker_select_specific");
+    let mut seeds = 0;
+    for entry in fs::read_dir(dir).unwrap() {
+        let (column, queries) = differential::decode_workload(&fs::read(entry.unwrap().path()).unwrap());
+        assert!(!column.is_empty() && !queries.is_empty());
+        assert_eq!(differential::check_select_specific(&column, &queries), Ok(()));
+        seeds += 1;
+    }
+    assert_eq!(seeds, 8);
+}
+
+#[test]
+fn workloads_survive_encoding() {
+    let column = vec![3, -1, 0, 300, 3];
+    let queries = vec![3, 7, -2];
+    assert_eq!(differential::decode_workload(&differential::encode_workload(&column, &queries)), (column, queries));
+}
+
+// == WORKLOADS ==
+
+fn all_patterns() -> Vec<Pattern> {
+    vec![Pattern::Random, Pattern::Sequential, Pattern::Skewed(1.0), Pattern::Periodic(7), Pattern::ZoomIn, Pattern::ZoomOut]
+}
+
+#[test]
+fn workload_queries_stay_in_domain_with_given_selectivity() {
+    for pattern in all_patterns() {
+        for q in workload::generate(pattern, 10, 109, 0.05, 200, 7) {
+            assert!(10 <= q.low && q.high <= 109, "{:?} generated {:?}", pattern, q);
+            assert_eq!(q.high - q.low + 1, 5, "{:?} generated {:?}", pattern, q);
+        }
+    }
+}
+
+#[test]
+fn workloads_cover_domains_at_the_ends_of_i64() {
+    // Skewed workloads keep a weight per position, so aren't for domains this wide.
+    let patterns: Vec<Pattern> = all_patterns().into_iter().filter(|p| !matches!(*p, Pattern::Skewed(_))).collect();
+    for &(low, high) in &[(i64::MIN, -1), (0, i64::MAX), (i64::MIN, i64::MAX - 1), (i64::MAX - 9, i64::MAX)] {
+        for &pattern in &patterns {
+            for q in workload::generate(pattern, low, high, 0.5, 20, 7) {
+                assert!(low <= q.low && q.low <= q.high && q.high <= high, "{:?} over {}..{} generated {:?}", pattern, low, high, q);
+            }
+        }
+    }
+    assert_eq!(workload::generate(Pattern::Sequential, i64::MAX - 9, i64::MAX, 0.5, 3, 0),
+               vec![Query { low: i64::MAX - 9, high: i64::MAX - 5 }, Query { low: i64::MAX - 4, high: i64::MAX }, Query { low: i64::MAX - 9, high: i64::MAX - 5 }]);
+}
+
+#[test]
+#[should_panic(expected = "generate: Domain -9223372036854775808..9223372036854775807 has 18446744073709551616 values, more than 18446744073709551615")]
+fn workloads_reject_the_whole_of_i64() {
+    workload::generate(Pattern::Random, i64::MIN, i64::MAX, 0.5, 1, 0);
+}
+
+#[test]
+fn workloads_are_reproducible_from_their_seed() {
+    for pattern in all_patterns() {
+        assert_eq!(workload::generate(pattern, 1, 1000, 0.01, 100, 42), workload::generate(pattern, 1, 1000, 0.01, 100, 42));
+    }
+    assert!(workload::generate(Pattern::Random, 1, 1000, 0.01, 100, 42) != workload::generate(Pattern::Random, 1, 1000, 0.01, 100, 43));
+}
+
+#[test]
+fn sequential_workload_sweeps_the_domain() {
+    let queries = workload::generate(Pattern::Sequential, 1, 10, 0.2, 6, 0);
+    let lows: Vec<i64> = queries.iter().map(|q| q.low).collect();
+    assert_eq!(lows, vec![1, 3, 5, 7, 9, 1]);
+}
+
+#[test]
+fn zoom_workloads_move_between_middle_and_ends() {
+    let zoom_in = workload::generate(Pattern::ZoomIn, 0, 9, 0.1, 10, 0);
+    assert_eq!(zoom_in[0], Query { low: 0, high: 0 });
+    assert_eq!(zoom_in[1], Query { low: 9, high: 9 });
+    assert_eq!(zoom_in[8], Query { low: 4, high: 4 });
+    assert_eq!(zoom_in[9], Query { low: 5, high: 5 });
+
+    let zoom_out: Vec<i64> = workload::generate(Pattern::ZoomOut, 0, 9, 0.1, 10, 0).iter().map(|q| q.low).collect();
+    assert_eq!(zoom_out, vec![4, 5, 3, 6, 2, 7, 1, 8, 0, 9]);
+}
+
+#[test]
+fn skewed_workload_favours_the_low_end() {
+    let queries = workload::generate(Pattern::Skewed(1.5), 1, 1000, 0.001, 1000, 3);
+    let hot = queries.iter().filter(|q| q.low <= 100).count();
+    assert!(hot > 800, "only {} of 1000 queries were in the hottest tenth", hot);
+}
+
+#[test]
+fn workloads_run_against_tables() {
+    let src = vec![4, 16, 22, 8, 26, 13, 22, 4, 18, 12, 13, 22, 10, 14, 22, 8, 19, 29, 8, 8, 17, 18, 22, 5, 2, 28, 8, 12, 24, 13, 15, 21, 30, 6, 18, 25, 7, 9, 19, 19, 4, 3, 11, 17, 28, 10, 8, 28, 22, 11, 29, 1, 20, 30, 8, 23, 4, 27];
+    let positions: Vec<i64> = (0..src.len() as i64).collect();
+    for pattern in all_patterns() {
+        let queries = workload::generate(pattern, 1, 30, 0.1, 20, 11);
+        let mut underswap = underswap_rle_compression::from_adjacency_vectors(src.clone(), positions.clone(), "src");
+        let mut selected = 0;
+        let times = workload::run(&queries, |x| {
+            let selection = underswap.cracker_select_specific(x, "dst");
+            selected += selection.len();
+            selection
+        });
+        assert_eq!(times.len(), queries.len());
+        let expected: usize = queries.iter().map(|q| src.iter().filter(|&&v| q.low <= v && v <= q.high).count()).sum();
+        assert_eq!(selected, expected, "{:?}", pattern);
+    }
+}
+
+// == DATA GENERATION ==
+
+#[test]
+fn generated_graphs_are_reproducible_from_their_seed() {
+    assert_eq!(datagen::randomly_connected_tree(100, 7), datagen::randomly_connected_tree(100, 7));
+    assert!(datagen::randomly_connected_tree(100, 7) != datagen::randomly_connected_tree(100, 8));
+    assert_eq!(datagen::randomly_connected_graph(50, 3.0, 7), datagen::randomly_connected_graph(50, 3.0, 7));
+    assert!(datagen::randomly_connected_graph(50, 3.0, 7) != datagen::randomly_connected_graph(50, 3.0, 8));
+    assert_eq!(datagen::random_start_node(100, 7), datagen::random_start_node(100, 7));
+}
+
+#[test]
+fn generated_trees_are_connected() {
+    for seed in 0..10 {
+        let n = 50;
+        let (src, dst) = datagen::randomly_connected_tree(n, seed);
+        assert_eq!(src.len(), 2 * (n as usize - 1));
+        let start_node = datagen::random_start_node(n, seed);
+        let mut visited = vec![start_node];
+        let mut i = 0;
+        while i < visited.len() {
+            let v = visited[i];
+            for j in 0..src.len() {
+                if src[j] == v && !visited.contains(&dst[j]) {
+                    visited.push(dst[j]);
+                }
+            }
+            i += 1;
+        }
+        assert_eq!(visited.len(), n as usize, "seed {}", seed);
+    }
+}
+
+fn all_models() -> Vec<datagen::Model> {
+    vec![
+        datagen::Model::Tree(40),
+        datagen::Model::RMat { scale: 6, edge_factor: 4, a: 0.57, b: 0.19, c: 0.19 },
+        datagen::Model::BarabasiAlbert { n: 40, m: 3 },
+        datagen::Model::Gnp { n: 40, p: 0.1 },
+        datagen::Model::Gnm { n: 40, m: 100 },
+        datagen::Model::Grid { rows: 5, cols: 8 },
+        datagen::Model::Sbm { block_sizes: vec![10, 20, 10], p: vec![vec![0.5, 0.05, 0.0], vec![0.05, 0.3, 0.05], vec![0.0, 0.05, 0.5]] },
+    ]
+}
+
+// Edges as (src, dst) pairs in ascending order.
+fn sorted_edges(src: &[i64], dst: &[i64]) -> Vec<(i64, i64)> {
+    let mut edges: Vec<(i64, i64)> = src.iter().cloned().zip(dst.iter().cloned()).collect();
+    edges.sort();
+    edges
+}
+
+#[test]
+fn models_are_reproducible_and_stay_in_range() {
+    for model in all_models() {
+        let (src, dst) = datagen::generate(&model, 3);
+        assert_eq!((src.clone(), dst.clone()), datagen::generate(&model, 3), "{:?}", model);
+        assert_eq!(src.len(), dst.len());
+        let n = model.nodes();
+        for i in 0..src.len() {
+            assert!(1 <= src[i] && src[i] <= n && 1 <= dst[i] && dst[i] <= n, "{:?}: edge {} -> {}", model, src[i], dst[i]);
+            assert!(src[i] != dst[i], "{:?}: self loop on {}", model, src[i]);
+        }
+    }
+}
+
+#[test]
+fn undirected_models_give_edges_in_both_directions() {
+    for model in all_models() {
+        if let datagen::Model::RMat { .. } = model {
+            continue;
+        }
+        let (src, dst) = datagen::generate(&model, 5);
+        assert_eq!(sorted_edges(&src, &dst), sorted_edges(&dst, &src), "{:?}", model);
+    }
+}
+
+#[test]
+#[should_panic(expected = "rmat: Quadrant probabilities b and c are both 0, so every edge is a self loop")]
+fn rmat_rejects_probabilities_which_only_give_self_loops() {
+    datagen::rmat(4, 8, 1.0, 0.0, 0.0, 1);
+}
+
+#[test]
+#[should_panic(expected = "stream_rmat: Quadrant probabilities b and c are both 0, so every edge is a self loop")]
+fn stream_rmat_rejects_probabilities_which_only_give_self_loops() {
+    datagen::stream_rmat(4, 8, 0.5, 0.0, 0.0, 1).count();
+}
+
+#[test]
+fn models_have_expected_edge_counts() {
+    let (src, _) = datagen::rmat(6, 4, 0.57, 0.19, 0.19, 1);
+    assert_eq!(src.len(), 4 * 64);
+
+    // A clique of 4 nodes, then 36 nodes attached with 3 edges each.
+    let (src, _) = datagen::barabasi_albert(40, 3, 1);
+    assert_eq!(src.len(), 2 * (6 + 36 * 3));
+
+    let (src, dst) = datagen::grid_2d(5, 8, 1);
+    assert_eq!(src.len(), 2 * (5 * 7 + 4 * 8));
+    assert!(sorted_edges(&src, &dst).contains(&(8, 16)));
+    assert!(!sorted_edges(&src, &dst).contains(&(8, 9)));
+
+    for &m in &[0, 100, 700, 780] {
+        let (src, dst) = datagen::erdos_renyi_gnm(40, m, 1);
+        let mut edges = sorted_edges(&src, &dst);
+        edges.dedup();
+        assert_eq!(edges.len(), 2 * m);
+    }
+
+    let (src, _) = datagen::erdos_renyi_gnp(40, 0.0, 1);
+    assert!(src.is_empty());
+    let (src, dst) = datagen::erdos_renyi_gnp(40, 1.0, 1);
+    let mut edges = sorted_edges(&src, &dst);
+    edges.dedup();
+    assert_eq!(edges.len(), 40 * 39);
+}
+
+#[test]
+fn stochastic_block_model_respects_block_probabilities() {
+    let p = vec![vec![1.0, 0.0], vec![0.0, 0.2]];
+    let (src, dst) = datagen::stochastic_block_model(&[10, 30], &p, 9);
+    let first_block = src.iter().filter(|&&u| u <= 10).count();
+    assert_eq!(first_block, 10 * 9);
+    for i in 0..src.len() {
+        assert_eq!(src[i] <= 10, dst[i] <= 10);
+    }
+}
+
+fn is_connected(n: i64, src: &[i64], dst: &[i64]) -> bool {
+    let mut neighbours: HashMap<i64, Vec<i64>> = HashMap::new();
+    for i in 0..src.len() {
+        neighbours.entry(src[i]).or_default().push(dst[i]);
+    }
+    let mut visited = vec![false; n as usize + 1];
+    let mut frontier = vec![1];
+    visited[1] = true;
+    let mut count = 1;
+    while let Some(u) = frontier.pop() {
+        for &v in neighbours.get(&u).map(|vs| vs.as_slice()).unwrap_or(&[]) {
+            if !visited[v as usize] {
+                visited[v as usize] = true;
+                count += 1;
+                frontier.push(v);
+            }
+        }
+    }
+    count == n
+}
+
+#[test]
+fn permutations_are_bijections() {
+    for &n in &[1, 2, 3, 5, 16, 17, 1000] {
+        let p = datagen::Permutation::new(n, 4);
+        let mut image: Vec<u64> = (0..n).map(|x| p.apply(x)).collect();
+        image.sort();
+        assert_eq!(image, (0..n).collect::<Vec<u64>>());
+    }
+    let identity: Vec<u64> = (0..1000).collect();
+    assert!((0..1000).map(|x| datagen::Permutation::new(1000, 4).apply(x)).collect::<Vec<u64>>() != identity);
+}
+
+#[test]
+fn streamed_graphs_match_their_models() {
+    let (src, dst): (Vec<i64>, Vec<i64>) = datagen::stream_tree(500, 2).unzip();
+    assert_eq!(src.len(), 2 * 499);
+    assert!(is_connected(500, &src, &dst));
+    assert_eq!(datagen::stream_tree(500, 2).collect::<Vec<(i64, i64)>>(), datagen::stream_tree(500, 2).collect::<Vec<(i64, i64)>>());
+
+    let (src, dst): (Vec<i64>, Vec<i64>) = datagen::stream_grid(5, 8).unzip();
+    let (grid_src, grid_dst) = datagen::grid_2d(5, 8, 0);
+    assert_eq!(sorted_edges(&src, &dst), sorted_edges(&grid_src, &grid_dst));
+
+    let (src, dst): (Vec<i64>, Vec<i64>) = datagen::stream_gnp(30, 1.0, 2).unzip();
+    let mut edges = sorted_edges(&src, &dst);
+    edges.dedup();
+    assert_eq!(edges.len(), 30 * 29);
+    assert_eq!(datagen::stream_gnp(30, 0.0, 2).count(), 0);
+
+    let edges: Vec<(i64, i64)> = datagen::stream_rmat(6, 4, 0.57, 0.19, 0.19, 2).collect();
+    assert_eq!(edges.len(), 4 * 64);
+    assert!(edges.iter().all(|&(u, v)| u != v && (1..=64).contains(&u) && (1..=64).contains(&v)));
+}
+
+// The fraction of vertices with each degree from 1 to 4, and with more, over trees of n nodes from
+// the given seeds.
+fn tree_degree_histogram<F>(n: i64, seeds: usize, tree: F) -> Vec<f64> where F: Fn(i64, usize) -> (Vec<i64>, Vec<i64>) {
+    let mut histogram = vec![0.0; 5];
+    for seed in 0..seeds {
+        let (src, _) = tree(n, seed);
+        let mut degree = HashMap::new();
+        for v in src {
+            *degree.entry(v).or_insert(0) += 1;
+        }
+        for d in degree.values() {
+            histogram[(*d as usize).min(5) - 1] += 1.0 / (n as f64 * seeds as f64);
+        }
+    }
+    histogram
+}
+
+#[test]
+fn streamed_trees_have_the_degrees_of_generated_trees() {
+    let generated = tree_degree_histogram(5000, 10, datagen::randomly_connected_tree);
+    let streamed = tree_degree_histogram(5000, 10, |n, seed| datagen::stream_tree(n, seed).unzip());
+    // Attaching in proportion to degree leaves two thirds of the nodes as leaves, where attaching
+    // uniformly would leave half.
+    assert!((generated[0] - 2.0 / 3.0).abs() < 0.02, "{:?}", generated);
+    for d in 0..5 {
+        assert!((generated[d] - streamed[d]).abs() < 0.02, "generated {:?}, streamed {:?}", generated, streamed);
+    }
+}
+
+#[test]
+fn large_trees_are_connected_and_streamed_in_full() {
+    let n = 200000;
+    let (src, dst) = datagen::randomly_connected_tree(n, 1);
+    assert!(is_connected(n, &src, &dst));
+    assert_eq!(datagen::stream_tree(1000000, 1).count(), 2 * 999999);
+}
+
+#[test]
+fn streamed_edges_are_written_out() {
+    let path = env::temp_dir().join("datagen_stream_tree.txt");
+    let path = path.to_str().unwrap();
+    assert_eq!(datagen::write_edge_list(datagen::stream_tree(100, 3), path).unwrap(), 198);
+    let written = fs::read_to_string(path).unwrap();
+    let _ = fs::remove_file(path);
+    let edges: Vec<(i64, i64)> = written.lines().map(|l| {
+        let mut fields = l.split('\t').map(|f| f.parse::<i64>().unwrap());
+        (fields.next().unwrap(), fields.next().unwrap())
+    }).collect();
+    assert_eq!(edges, datagen::stream_tree(100, 3).collect::<Vec<(i64, i64)>>());
+}
+
+// == LOADERS ==
+
+#[test]
+fn snap_edge_lists_load() {
+    let edges = load_graph::parse_snap(Cursor::new("# Directed graph\n# FromNodeId\tToNodeId\n0\t1\n1 2\n\n2\t0\n")).unwrap();
+    assert_eq!((edges.src.clone(), edges.dst.clone()), (vec![0, 1, 2], vec![1, 2, 0]));
+    assert_eq!(edges.weights, None);
+    let mut table = underswap_rle_compression::from_adjacency_vectors(edges.src, edges.dst, "src");
+    assert_eq!(table.cracker_select_specific(1, "dst"), vec![2]);
+
+    let edges = load_graph::parse_snap(Cursor::new("1 2 0.5\n2 3 1.5\n")).unwrap();
+    assert_eq!(edges.weights, Some(vec![0.5, 1.5]));
+
+    assert_eq!(load_graph::parse_snap(Cursor::new("1 2\n2 x\n")), Err("line 2: Invalid destination vertex 'x'".to_string()));
+    assert_eq!(load_graph::parse_snap(Cursor::new("1 2\n2 3 4\n")), Err("line 2: Some edges have weights and some don't".to_string()));
+}
+
+#[test]
+fn matrix_market_files_load() {
+    let mm = "%%MatrixMarket matrix coordinate real symmetric\n% comment\n3 3 3\n2 1 0.5\n3 3 2\n3 2 1\n";
+    let edges = load_graph::parse_matrix_market(Cursor::new(mm)).unwrap();
+    assert_eq!(edges.src, vec![2, 1, 3, 3, 2]);
+    assert_eq!(edges.dst, vec![1, 2, 3, 2, 3]);
+    assert_eq!(edges.weights, Some(vec![0.5, 0.5, 2.0, 1.0, 1.0]));
+    assert_eq!(edges.nodes, Some(3));
+
+    let edges = load_graph::parse_matrix_market(Cursor::new("%%MatrixMarket matrix coordinate pattern general\n2 2 1\n1 2\n")).unwrap();
+    assert_eq!((edges.src, edges.dst, edges.weights), (vec![1], vec![2], None));
+
+    assert_eq!(load_graph::parse_matrix_market(Cursor::new("%%MatrixMarket matrix array real general\n")), Err("line 1: Only coordinate matrices are supported, not array".to_string()));
+    assert_eq!(load_graph::parse_matrix_market(Cursor::new("%%MatrixMarket matrix coordinate pattern general\n2 2 2\n1 3\n")), Err("line 3: Vertex 3 is outside of 1..2".to_string()));
+    assert_eq!(load_graph::parse_matrix_market(Cursor::new("%%MatrixMarket matrix coordinate pattern general\n2 2 2\n1 2\n")), Err("line 3: Only 1 of the 2 declared entries".to_string()));
+}
+
+#[test]
+fn metis_files_load() {
+    // A triangle 1-2-3 with vertex 4 isolated.
+    let edges = load_graph::parse_metis(Cursor::new("% comment\n4 3\n2 3\n1 3\n1 2\n\n")).unwrap();
+    assert_eq!(edges.src, vec![1, 1, 2, 2, 3, 3]);
+    assert_eq!(edges.dst, vec![2, 3, 1, 3, 1, 2]);
+    assert_eq!(edges.nodes, Some(4));
+
+    let edges = load_graph::parse_metis(Cursor::new("2 1 011\n7 2 5\n8 1 5\n")).unwrap();
+    assert_eq!((edges.src, edges.dst, edges.weights), (vec![1, 2], vec![2, 1], Some(vec![5.0, 5.0])));
+
+    assert_eq!(load_graph::parse_metis(Cursor::new("3 1\n2\n1\n")), Err("line 3: Only 2 of the 3 declared vertices".to_string()));
+    assert_eq!(load_graph::parse_metis(Cursor::new("2 1 001\n2\n")), Err("line 2: Missing edge weight".to_string()));
+}
+
+#[test]
+fn dimacs_files_load() {
+    let gr = "c 9th DIMACS\np sp 3 2\nc arcs\na 1 2 7\na 2 3 4\n";
+    let edges = load_graph::parse_dimacs(Cursor::new(gr)).unwrap();
+    assert_eq!((edges.src, edges.dst, edges.weights), (vec![1, 2], vec![2, 3], Some(vec![7.0, 4.0])));
+
+    assert_eq!(load_graph::parse_dimacs(Cursor::new("a 1 2 3\n")), Err("line 1: Arc before the problem line".to_string()));
+    assert_eq!(load_graph::parse_dimacs(Cursor::new("p sp 2 1\na 1 2 3\na 2 1 3\n")), Err("line 3: More than the 1 declared arcs".to_string()));
+}
+
+#[test]
+fn load_errors_name_the_file() {
+    let path = env::temp_dir().join("load_graph_bad.txt");
+    let path = path.to_str().unwrap();
+    fs::write(path, "1 2\n3\n").unwrap();
+    let result = load_graph::load_snap(path);
+    let _ = fs::remove_file(path);
+    assert_eq!(result, Err(format!("{}: line 2: Missing destination vertex", path)));
+}
+
+fn social_network_dir(name: &str, files: &[&str]) -> PathBuf {
+    let dir = env::temp_dir().join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    for f in files {
+        fs::write(dir.join(f), "").unwrap();
+    }
+    dir
+}
+
+#[test]
+fn dataset_config_parses() {
+    let registry = datasets::Registry::parse("# LDBC\ndata_home = /data/ldbc\n\nsf0.1 = /tmp/small\n").unwrap();
+    assert_eq!(registry.config_data_home, Some(PathBuf::from("/data/ldbc")));
+    assert_eq!(registry.scale_factors.get("0.1"), Some(&PathBuf::from("/tmp/small")));
+    assert_eq!(datasets::Registry::parse("data_home\n"), Err("line 1: Expected 'key = value'".to_string()));
+    assert_eq!(datasets::Registry::parse("home = /data\n"), Err("line 1: Unknown key 'home'".to_string()));
+}
+
+#[test]
+fn datasets_resolve_and_validate() {
+    let home = social_network_dir("datasets_home", &[]);
+    let sf3 = home.join("social_network_sf3");
+    fs::create_dir_all(&sf3).unwrap();
+    fs::write(sf3.join(datasets::NODES_FILE), "").unwrap();
+    fs::write(sf3.join(datasets::EDGES_FILE), "").unwrap();
+    let elsewhere = social_network_dir("datasets_sf0.1", &[datasets::NODES_FILE]);
+
+    let mut registry = datasets::Registry::default();
+    assert!(registry.dataset("3").unwrap_err().contains(datasets::DATA_HOME_VAR));
+
+    registry.config_data_home = Some(home.clone());
+    registry.scale_factors.insert("0.1".to_string(), elsewhere.clone());
+    let dataset = registry.dataset("3").unwrap();
+    assert_eq!(dataset.edges_path(), sf3.join(datasets::EDGES_FILE).to_string_lossy());
+    assert_eq!(registry.dataset("0.1"), Err(format!("LDBC scale factor 0.1: {} is missing {}", elsewhere.display(), datasets::EDGES_FILE)));
+    assert_eq!(registry.dataset("10"), Err(format!("LDBC scale factor 10: {} is not a directory", home.join("social_network_sf10").display())));
+
+    // The environment's data home takes precedence over the config file's.
+    registry.data_home = Some(elsewhere.clone());
+    assert!(registry.dataset("3").is_err());
+
+    let _ = fs::remove_dir_all(&home);
+    let _ = fs::remove_dir_all(&elsewhere);
+}
+
+#[test]
+fn ldbc_timestamps_parse() {
+    assert_eq!(load_ldbc::parse_timestamp("1970-01-01"), Ok(0));
+    assert_eq!(load_ldbc::parse_timestamp("1989-12-03"), Ok(628646400000));
+    assert_eq!(load_ldbc::parse_timestamp("2010-02-14T15:32:10.447+0000"), Ok(1266161530447));
+    assert_eq!(load_ldbc::parse_timestamp("2010-02-14T16:32:10.447+01:00"), Ok(1266161530447));
+    assert_eq!(load_ldbc::parse_timestamp("1969-07-20T20:17:40Z"), Ok(-14182940000));
+    assert_eq!(load_ldbc::parse_timestamp("1266161530447"), Ok(1266161530447));
+    assert!(load_ldbc::parse_timestamp("2010-13-01").is_err());
+    assert!(load_ldbc::parse_timestamp("2010-02-31").is_err());
+    assert!(load_ldbc::parse_timestamp("2010-04-31T00:00:00Z").is_err());
+    assert!(load_ldbc::parse_timestamp("1900-02-29").is_err());
+    assert_eq!(load_ldbc::parse_timestamp("2000-02-29"), Ok(951782400000));
+    assert_eq!(load_ldbc::parse_timestamp("2012-02-29"), Ok(1330473600000));
+}
+
+#[test]
+fn ldbc_relations_load_into_typed_tables() {
+    let people = "id|firstName|lastName|gender|birthday|creationDate|locationIP|browserUsed\n\
+                  933|Mahinda|Perera|male|1989-12-03|2010-02-14T15:32:10.447+0000|119.235.7.103|Firefox\n\
+                  4139|Baruch|Dego|male|1986-09-21|2010-01-28T06:39:58.313+0000|213.55.127.9|Chrome\n\
+                  6597|Carmen|Lepland|female|1984-02-18|2010-01-26T07:13:35.012+0000|195.20.151.175|Firefox\n";
+    let mut person = load_ldbc::read_relation(Cursor::new(people), &load_ldbc::PERSON).unwrap();
+    assert_eq!(person.table.count, 3);
+    assert_eq!(person.select_text(4139, "firstName"), vec!["Baruch".to_string()]);
+    assert_eq!(person.table.cracker_select_specific(933, "birthday"), vec![628646400000]);
+    assert_eq!(person.dictionaries["browserUsed"].values, vec!["Firefox".to_string(), "Chrome".to_string()]);
+    let firefox = person.dictionaries["browserUsed"].code("Firefox").unwrap();
+    assert_eq!(person.table.count_col_eq("browserUsed", firefox), 2);
+
+    // Posts made by the people 933 knows.
+    let knows = "Person.id|Person.id|creationDate\n933|4139|2010-03-13T07:37:21.718+0000\n933|6597|2010-03-14T07:37:21.718+0000\n4139|933|2010-03-13T07:37:21.718+0000\n";
+    let creators = "Post.id|Person.id\n1|933\n2|6597\n3|4139\n4|6597\n";
+    let mut knows = load_ldbc::read_relation(Cursor::new(knows), &load_ldbc::PERSON_KNOWS_PERSON).unwrap();
+    let creators = load_ldbc::read_relation(Cursor::new(creators), &load_ldbc::POST_HAS_CREATOR_PERSON).unwrap();
+    let mut created = decomposed_cracking::from_adjacency_vectors(creators.table.columns["dst"].v.clone(), creators.table.columns["src"].v.clone(), "src");
+    let mut posts: Vec<i64> = knows.table.cracker_select_specific(933, "dst").iter().flat_map(|&p| created.cracker_select_specific(p, "dst")).collect();
+    posts.sort();
+    assert_eq!(posts, vec![2, 3, 4]);
+}
+
+#[test]
+fn ldbc_load_errors_give_lines() {
+    let forums = "id|title|creationDate\n1|Wall of A|2010-02-14T15:32:10.447+0000\n2|Wall of B|yesterday\n";
+    let result = load_ldbc::read_relation(Cursor::new(forums), &load_ldbc::FORUM);
+    assert_eq!(result.err(), Some("line 3: Invalid creationDate 'yesterday': Expected yyyy-mm-dd".to_string()));
+    let result = load_ldbc::read_relation(Cursor::new("id|name\n1|x\n"), &load_ldbc::TAG);
+    assert_eq!(result.err(), Some("line 1: Expected the 3 columns of tag, found 2".to_string()));
+
+    let dir = social_network_dir("ldbc_partial", &[]);
+    fs::write(dir.join(datasets::NODES_FILE), "id|firstName|lastName|gender|birthday|creationDate|locationIP|browserUsed\n").unwrap();
+    fs::write(dir.join(datasets::EDGES_FILE), "Person.id|Person.id|creationDate\n").unwrap();
+    let dataset = datasets::Dataset::at(&dir).unwrap();
+    let result = load_ldbc::load_social_network(&dataset);
+    let _ = fs::remove_dir_all(&dir);
+    assert!(result.err().unwrap().starts_with(&format!("{}: ", dir.join("post_0_0.csv").display())));
+}
+
+#[test]
+fn synthetic_ldbc_sizes_follow_ldbc() {
+    assert_eq!(synthetic_ldbc::ldbc_size(1.0), (9892, 180623));
+    assert_eq!(synthetic_ldbc::ldbc_size(10.0), (65645, 1938516));
+    let (people, knows) = synthetic_ldbc::ldbc_size(3.0);
+    assert!(9892 < people && people < 65645 && 180623 < knows && knows < 1938516);
+    assert!(synthetic_ldbc::ldbc_size(300.0).0 > 448626);
+}
+
+#[test]
+fn synthetic_ldbc_networks_look_like_ldbc() {
+    let network = synthetic_ldbc::generate(0.1, 6);
+    assert_eq!(network, synthetic_ldbc::generate(0.1, 6));
+    let (people, knows) = synthetic_ldbc::ldbc_size(0.1);
+    assert_eq!(network.people.len(), people);
+    assert!((network.src.len() as f64 - knows as f64).abs() < 0.2 * knows as f64);
+
+    // IDs are unique, increase with creation date, and are sparse.
+    let ids = network.ids();
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    assert!(network.people.windows(2).all(|w| w[0].creation_date <= w[1].creation_date));
+    assert!(*ids.last().unwrap() > 1 << 41);
+
+    let mut degrees: HashMap<i64, usize> = HashMap::new();
+    let mut edges = HashSet::new();
+    for i in 0..network.src.len() {
+        assert!(ids.binary_search(&network.src[i]).is_ok() && ids.binary_search(&network.dst[i]).is_ok());
+        assert!(network.src[i] != network.dst[i]);
+        assert!(edges.insert((network.src[i].min(network.dst[i]), network.src[i].max(network.dst[i]))));
+        *degrees.entry(network.src[i]).or_default() += 1;
+        *degrees.entry(network.dst[i]).or_default() += 1;
+    }
+    // Degrees are skewed: the highest is several times the mean.
+    let mean = 2.0 * network.src.len() as f64 / people as f64;
+    assert!(*degrees.values().max().unwrap() as f64 > 4.0 * mean);
+}
+
+#[test]
+fn synthetic_ldbc_networks_load_like_ldbc() {
+    let network = synthetic_ldbc::generate(0.1, 2);
+    let dir = social_network_dir("synthetic_ldbc", &[]);
+    network.write(&dir).unwrap();
+    let dataset = datasets::Dataset::at(&dir).unwrap();
+    let person = load_ldbc::load_relation(&dataset, &load_ldbc::PERSON).unwrap();
+    let knows = load_ldbc::load_relation(&dataset, &load_ldbc::PERSON_KNOWS_PERSON).unwrap();
+    let _ = fs::remove_dir_all(&dir);
+
+    assert_eq!(person.table.columns["id"].v, network.ids());
+    assert_eq!(person.table.columns["birthday"].v, network.people.iter().map(|p| p.birthday).collect::<Vec<i64>>());
+    assert_eq!(person.table.columns["creationDate"].v, network.people.iter().map(|p| p.creation_date).collect::<Vec<i64>>());
+    assert_eq!(knows.table.columns["src"].v, network.src);
+    assert_eq!(knows.table.columns["creationDate"].v, network.creation_dates);
+}
+
+// == VERTEX DICTIONARY ==
+
+#[test]
+fn vertex_dictionary_round_trips() {
+    let mut dictionary = VertexDictionary::from_vertices(&[933, 0, -5]);
+    assert_eq!(dictionary.intern(4398046512167), 4);
+    assert_eq!(dictionary.intern(0), 2);
+    assert_eq!(dictionary.len(), 4);
+    assert_eq!(dictionary.internal(-5), Some(3));
+    assert_eq!(dictionary.internal(7), None);
+    assert_eq!(dictionary.external(1), Some(933));
+    assert_eq!(dictionary.external(0), None);
+    assert_eq!(dictionary.external(5), None);
+    assert_eq!(dictionary.decode(&[4, 1]), vec![4398046512167, 933]);
+
+    let (src, dst) = dictionary.encode_edges(&[0, 12], &[-5, 933]);
+    assert_eq!((src, dst), (vec![2, 5], vec![3, 1]));
+    let ranks = dictionary.decode_values(&[0.0, 0.1, 0.2, 0.3, 0.4, 0.5]);
+    assert_eq!(ranks.len(), 5);
+    assert_eq!(ranks[&-5], 0.3);
+    assert_eq!(ranks[&12], 0.5);
+}
+
+#[test]
+fn traversals_work_on_sparse_ids_through_a_dictionary() {
+    // A tree over LDBC-like IDs, along with zero and negative IDs.
+    let (src, dst) = datagen::randomly_connected_tree(40, 12);
+    let ids: Vec<i64> = (0..40).map(|i| if i % 3 == 0 { -i } else { i * 2199023255552 + 933 }).collect();
+    let sparse_src: Vec<i64> = src.iter().map(|&v| ids[v as usize - 1]).collect();
+    let sparse_dst: Vec<i64> = dst.iter().map(|&v| ids[v as usize - 1]).collect();
+
+    let (dictionary, dense_src, dense_dst) = vertex_dictionary::densify(&sparse_src, &sparse_dst);
+    assert_eq!(dictionary.len(), 40);
+    assert!(dense_src.iter().chain(dense_dst.iter()).all(|v| (1..=40).contains(v)));
+    assert!(bfs_test(40, dense_src, dense_dst, dictionary.internal(ids[7]).unwrap()));
+}
+
+type BfsKernel = fn(Vec<i64>, Vec<i64>, i64) -> Vec<i64>;
+
+#[test]
+fn bfs_kernels_visit_zero_negative_and_wide_ids() {
+    let kernels: Vec<(&str, BfsKernel)> = vec![
+        ("Unoptimised", bfs::unoptimised_bfs),
+        ("Preclustered", bfs::preclustered_bfs),
+        ("Preclustered RLE", bfs::preclustered_rle_bfs),
+        ("Decracked", bfs::decracked_bfs),
+        ("Reco", bfs::reco_bfs),
+        ("Coco", bfs::coco_bfs),
+        ("Underswap RLE", bfs::underswap_rle_bfs),
+        ("Overswap RLE", bfs::overswap_rle_bfs),
+    ];
+    // 0 - -5 - 1 << 40 - 7 in both directions, and 8 -> 9 apart from them.
+    let wide = 1 << 40;
+    let src = vec![0, -5, -5, wide, wide, 7, 8];
+    let dst = vec![-5, 0, wide, -5, 7, wide, 9];
+    for (name, bfs) in kernels {
+        let mut visited = bfs(src.clone(), dst.clone(), wide);
+        visited.sort();
+        assert_eq!(visited, vec![-5, 0, 7, wide], "{}", name);
+        assert_eq!(bfs(src.clone(), dst.clone(), 8), vec![8, 9], "{}", name);
+        assert_eq!(bfs(src.clone(), dst.clone(), -12), vec![-12], "{}: start node without edges", name);
+    }
+}
+
+// == EXPORT ==
+
+fn export_example() -> (Vec<i64>, Vec<i64>) {
+    (vec![1, 1, 2, 3, 4], vec![2, 3, 4, 4, 5])
+}
+
+#[test]
+fn graphs_export_to_dot() {
+    let (src, dst) = export_example();
+    let mut export = Export::new(&src, &dst);
+    let depths: HashMap<i64, usize> = map!{1 => 0, 2 => 1, 3 => 1};
+    export.add_attribute("depth", &depths);
+    export.add_attribute("name", &map!{1 => "a \"quoted\" name"});
+    let dot = export.to_dot();
+    assert!(dot.starts_with("digraph G {\n"));
+    assert!(dot.contains("  1 [label=\"1\\ndepth=0\\nname=a \\\"quoted\\\" name\", \"depth\"=\"0\", \"name\"=\"a \\\"quoted\\\" name\"];\n"));
+    assert!(dot.contains("  5 [label=\"5\"];\n"));
+    assert!(dot.contains("  3 -> 4;\n"));
+    assert_eq!(dot.matches("->").count(), 5);
+}
+
+#[test]
+fn graphs_export_to_graphml_and_json() {
+    let (src, dst) = export_example();
+    let mut export = Export::new(&src, &dst);
+    export.add_attribute("rank", &map!{4 => 0.5, 5 => 0.25});
+    let graphml = export.to_graphml();
+    assert!(graphml.contains("<key id=\"d0\" for=\"node\" attr.name=\"rank\" attr.type=\"double\"/>"));
+    assert!(graphml.contains("<node id=\"n4\"><data key=\"d0\">0.5</data></node>"));
+    assert!(graphml.contains("<node id=\"n1\"/>"));
+    assert!(graphml.contains("<edge id=\"e4\" source=\"n4\" target=\"n5\"/>"));
+
+    assert_eq!(export.to_json(), "{\"directed\": true, \"nodes\": [{\"id\": 1}, {\"id\": 2}, {\"id\": 3}, {\"id\": 4, \"rank\": 0.5}, {\"id\": 5, \"rank\": 0.25}], \"links\": [{\"source\": 1, \"target\": 2}, {\"source\": 1, \"target\": 3}, {\"source\": 2, \"target\": 4}, {\"source\": 3, \"target\": 4}, {\"source\": 4, \"target\": 5}]}\n");
+}
+
+#[test]
+fn exports_restrict_to_subgraphs() {
+    let (src, dst) = export_example();
+    let mut export = Export::new(&src, &dst);
+    export.restrict_to_neighbourhood(2, 1);
+    assert_eq!(export.to_json(), "{\"directed\": true, \"nodes\": [{\"id\": 2}, {\"id\": 4}], \"links\": [{\"source\": 2, \"target\": 4}]}\n");
+    export.restrict_to(vec![1, 3, 5].into_iter().collect());
+    assert_eq!(export.to_dot(), "digraph G {\n  1 [label=\"1\"];\n  3 [label=\"3\"];\n  5 [label=\"5\"];\n  1 -> 3;\n}\n");
+    assert_eq!(export::neighbourhood(&src, &dst, 1, 2), vec![1, 2, 3, 4].into_iter().collect());
+
+    let path = env::temp_dir().join("export_subgraph.dot");
+    let path = path.to_str().unwrap();
+    export.write_dot(path).unwrap();
+    let written = fs::read_to_string(path).unwrap();
+    let _ = fs::remove_file(path);
+    assert_eq!(written, export.to_dot());
+}
+
+// == GRAPH VIEWS ==
+
+// Every storage method behind the Graph trait, over the same adjacency list.
+fn all_graphs(src: &[i64], dst: &[i64]) -> Vec<Box<dyn Graph>> {
+    vec![
+        Box::new(graph::DeCrackedGraph::new(src.to_vec(), dst.to_vec())),
+        Box::new(graph::ReCoGraph::new(src.to_vec(), dst.to_vec())),
+        Box::new(graph::CoCoGraph::new(src.to_vec(), dst.to_vec())),
+        Box::new(graph::UnderswapRLEGraph::new(src.to_vec(), dst.to_vec())),
+        Box::new(graph::OverswapRLEGraph::new(src.to_vec(), dst.to_vec())),
+        Box::new(graph::PreclusteredGraph::new(src.to_vec(), dst.to_vec())),
+        Box::new(graph::CsrGraph::new(src.to_vec(), dst.to_vec())),
+        Box::new(graph::ScanGraph::new(src.to_vec(), dst.to_vec())),
+    ]
+}
+
+fn sorted(mut xs: Vec<i64>) -> Vec<i64> {
+    xs.sort();
+    xs
+}
+
+// A graph which counts the lookups of each vertex's neighbours.
+struct LookupCounter<G> {
+    graph: G,
+    out_lookups: HashMap<i64, usize>,
+    in_lookups: HashMap<i64, usize>,
+}
+
+impl<G: Graph> LookupCounter<G> {
+    fn new(graph: G) -> LookupCounter<G> {
+        LookupCounter { graph, out_lookups: HashMap::new(), in_lookups: HashMap::new() }
+    }
+}
+
+impl<G: Graph> Graph for LookupCounter<G> {
+    fn vertices(&self) -> &[i64] {
+        self.graph.vertices()
+    }
+
+    fn edge_count(&self) -> usize {
+        self.graph.edge_count()
+    }
+
+    fn out_neighbours(&mut self, v: i64) -> Vec<i64> {
+        *self.out_lookups.entry(v).or_insert(0) += 1;
+        self.graph.out_neighbours(v)
+    }
+
+    fn in_neighbours(&mut self, v: i64) -> Vec<i64> {
+        *self.in_lookups.entry(v).or_insert(0) += 1;
+        self.graph.in_neighbours(v)
+    }
+}
+
+#[test]
+fn graphs_agree_with_a_scan_of_the_adjacency_list() {
+    for model in all_models() {
+        let (src, dst) = datagen::generate(&model, 5);
+        for mut g in all_graphs(&src, &dst) {
+            let vertices = g.vertices().to_vec();
+            assert_eq!(vertices, sorted(src.iter().chain(dst.iter()).cloned().collect::<HashSet<i64>>().into_iter().collect()));
+            for &v in &vertices {
+                let out: Vec<i64> = (0..src.len()).filter(|&i| src[i] == v).map(|i| dst[i]).collect();
+                let inn: Vec<i64> = (0..src.len()).filter(|&i| dst[i] == v).map(|i| src[i]).collect();
+                assert_eq!(g.out_degree(v), out.len(), "{:?}: out degree of {}", model, v);
+                assert_eq!(sorted(g.out_neighbours(v)), sorted(out), "{:?}: out neighbours of {}", model, v);
+                assert_eq!(sorted(g.in_neighbours(v)), sorted(inn), "{:?}: in neighbours of {}", model, v);
+            }
+        }
+    }
+}
+
+#[test]
+fn graphs_handle_sparse_ids_and_missing_vertices() {
+    let src = vec![-5, 1 << 40, 0, 0];
+    let dst = vec![0, -5, 1 << 40, 7];
+    for mut g in all_graphs(&src, &dst) {
+        assert_eq!(g.vertices(), &[-5, 0, 7, 1 << 40][..]);
+        assert_eq!(sorted(g.out_neighbours(0)), vec![7, 1 << 40]);
+        assert_eq!(g.in_neighbours(-5), vec![1 << 40]);
+        assert_eq!(g.out_neighbours(7), vec![]);
+        assert_eq!(g.out_degree(3), 0);
+        assert_eq!(g.in_neighbours(3), vec![]);
+    }
+}
+
+// A traversal written once against the trait gives the same reachable set on every storage method.
+#[test]
+fn generic_traversals_run_on_every_graph() {
+    let (src, dst) = datagen::randomly_connected_tree(60, 2);
+    for mut g in all_graphs(&src, &dst) {
+        let mut reached: HashSet<i64> = HashSet::new();
+        reached.insert(1);
+        let mut frontier = vec![1];
+        while let Some(v) = frontier.pop() {
+            for u in g.out_neighbours(v) {
+                if reached.insert(u) {
+                    frontier.push(u);
+                }
+            }
+        }
+        assert_eq!(reached.len(), 60);
+    }
+}
+
+// == TRAVERSALS ==
+
+// Hop distances from START by repeated scans of the adjacency list.
+fn scanned_depths(src: &[i64], dst: &[i64], start: i64) -> HashMap<i64, usize> {
+    let mut depths = HashMap::new();
+    depths.insert(start, 0);
+    let mut depth = 0;
+    loop {
+        let next: Vec<i64> = (0..src.len()).filter(|&i| depths.get(&src[i]) == Some(&depth) && !depths.contains_key(&dst[i])).map(|i| dst[i]).collect();
+        if next.is_empty() {
+            return depths;
+        }
+        depth += 1;
+        for v in next {
+            depths.insert(v, depth);
+        }
+    }
+}
+
+#[test]
+fn bfs_trees_give_depths_and_shortest_paths() {
+    for model in all_models() {
+        let (src, dst) = datagen::generate(&model, 9);
+        let start = src[0];
+        let depths = scanned_depths(&src, &dst, start);
+        let edges: HashSet<(i64, i64)> = src.iter().cloned().zip(dst.iter().cloned()).collect();
+        for mut g in all_graphs(&src, &dst) {
+            let tree = traversal::bfs_tree(&mut *g, start);
+            assert_eq!(tree.order[0], start);
+            assert_eq!(tree.order.len(), depths.len(), "{:?}", model);
+            for (&v, &depth) in &depths {
+                assert_eq!(tree.depth(v), Some(depth), "{:?}: depth of {}", model, v);
+                let path = tree.path_to(v).unwrap();
+                assert_eq!(path.len(), depth + 1);
+                assert_eq!(path[0], start);
+                for w in path.windows(2) {
+                    assert!(edges.contains(&(w[0], w[1])), "{:?}: no edge {} -> {}", model, w[0], w[1]);
+                }
+            }
+            assert_eq!(tree.parent(start), None);
+        }
+    }
+}
+
+#[test]
+fn hop_queries_stop_at_their_depth() {
+    let (src, dst) = datagen::generate(&datagen::Model::Grid { rows: 6, cols: 6 }, 0);
+    let depths = scanned_depths(&src, &dst, 1);
+    for hops in 0..4 {
+        let mut expected: Vec<i64> = depths.iter().filter(|e| *e.1 <= hops).map(|e| *e.0).collect();
+        expected.sort();
+        assert_eq!(expected, sorted(export::neighbourhood(&src, &dst, 1, hops).into_iter().collect()));
+        for mut g in all_graphs(&src, &dst) {
+            assert_eq!(traversal::within_hops(&mut *g, 1, hops), expected);
+            assert_eq!(traversal::bfs_tree(&mut *g, 1).within(hops), expected);
+        }
+    }
+    let mut g = graph::CsrGraph::new(src.clone(), dst.clone());
+    assert_eq!(traversal::shortest_path(&mut g, 1, 1), Some(vec![1]));
+    assert_eq!(traversal::shortest_path(&mut g, 1, 36).map(|p| p.len()), Some(11));
+    assert_eq!(traversal::shortest_path(&mut g, 1, 37), None);
+}
+
+#[test]
+fn direction_optimising_bfs_visits_what_top_down_bfs_does() {
+    for model in all_models() {
+        let (src, dst) = datagen::generate(&model, 4);
+        let start = src[0];
+        let expected = sorted(scanned_depths(&src, &dst, start).keys().cloned().collect());
+        for mut g in all_graphs(&src, &dst) {
+            assert_eq!(traversal::direction_optimising_bfs(&mut *g, start), expected, "{:?}", model);
+        }
+    }
+}
+
+#[test]
+fn direction_optimising_bfs_switches_direction() {
+    let (src, dst) = datagen::generate(&datagen::Model::Gnp { n: 200, p: 0.05 }, 1);
+    let expected = sorted(scanned_depths(&src, &dst, 1).keys().cloned().collect());
+    let mut g = graph::DeCrackedGraph::new(src.clone(), dst.clone());
+    let (visited, steps) = traversal::direction_optimising_bfs_steps(&mut g, 1, traversal::ALPHA, traversal::BETA);
+    assert_eq!(visited, expected);
+    assert_eq!(steps[0], traversal::Step::TopDown);
+    assert!(steps.contains(&traversal::Step::BottomUp), "{:?}", steps);
+
+    // Never switching, or always switching, reaches the same vertices.
+    let (visited, steps) = traversal::direction_optimising_bfs_steps(&mut g, 1, 0, traversal::BETA);
+    assert_eq!(visited, expected);
+    assert!(steps.iter().all(|&s| s == traversal::Step::TopDown));
+    let (visited, steps) = traversal::direction_optimising_bfs_steps(&mut g, 1, usize::MAX, usize::MAX);
+    assert_eq!(visited, expected);
+    assert!(steps.iter().all(|&s| s == traversal::Step::BottomUp));
+    assert_eq!(traversal::direction_optimising_bfs(&mut g, 1000), vec![1000]);
+}
+
+#[test]
+fn direction_optimising_bfs_looks_up_out_neighbours_once() {
+    let (src, dst) = datagen::generate(&datagen::Model::Gnp { n: 200, p: 0.05 }, 1);
+    for &alpha in &[0, traversal::ALPHA] {
+        let mut g = LookupCounter::new(graph::DeCrackedGraph::new(src.clone(), dst.clone()));
+        let visited = traversal::direction_optimising_bfs_steps(&mut g, 1, alpha, traversal::BETA).0;
+        // Every vertex reached is looked up once, to count its edges on discovery.
+        assert_eq!(sorted(g.out_lookups.keys().cloned().collect()), visited);
+        assert!(g.out_lookups.values().all(|&c| c == 1), "{:?}", g.out_lookups);
+        assert_eq!(g.in_lookups.is_empty(), alpha == 0);
+    }
+}
+
+#[test]
+fn multi_source_bfs_matches_single_source_bfs() {
+    for model in all_models() {
+        let (src, dst) = datagen::generate(&model, 6);
+        // More than one batch, with a repeated source and one without edges.
+        let mut sources: Vec<i64> = (1..=model.nodes()).chain(vec![1, model.nodes() + 1]).collect();
+        sources.reverse();
+        for mut g in all_graphs(&src, &dst) {
+            let depths = traversal::multi_source_depths(&mut *g, &sources);
+            assert_eq!(depths.len(), sources.len());
+            for (i, &s) in sources.iter().enumerate() {
+                assert_eq!(depths[i], scanned_depths(&src, &dst, s), "{:?}: source {}", model, s);
+            }
+        }
+    }
+}
+
+#[test]
+fn closeness_and_hop_distribution_come_from_distances() {
+    // A path 1 - 2 - 3 - 4 and a separate vertex 5 with a loop.
+    let src = vec![1, 2, 2, 3, 3, 4, 5];
+    let dst = vec![2, 1, 3, 2, 4, 3, 5];
+    let mut g = graph::UnderswapRLEGraph::new(src, dst);
+    let closeness = traversal::closeness_centrality(&mut g, &[1, 2, 5]);
+    assert_eq!(closeness[&1], 3.0 / 6.0);
+    assert_eq!(closeness[&2], 3.0 / 4.0);
+    assert_eq!(closeness[&5], 0.0);
+    assert_eq!(traversal::hop_distribution(&mut g, &[1, 2, 3, 4, 5]), vec![5, 6, 4, 2]);
+}
+
+#[test]
+fn bidirectional_bfs_finds_shortest_paths() {
+    for model in all_models() {
+        let (src, dst) = datagen::generate(&model, 8);
+        let edges: HashSet<(i64, i64)> = src.iter().cloned().zip(dst.iter().cloned()).collect();
+        let n = model.nodes();
+        for mut g in all_graphs(&src, &dst) {
+            for from in (1..=n).step_by(7) {
+                let depths = scanned_depths(&src, &dst, from);
+                for to in (1..=n + 1).step_by(5) {
+                    let path = traversal::shortest_path(&mut *g, from, to);
+                    assert_eq!(path.as_ref().map(|p| p.len() - 1), depths.get(&to).cloned(), "{:?}: {} to {}", model, from, to);
+                    if let Some(path) = path {
+                        assert_eq!((path[0], path[path.len() - 1]), (from, to));
+                        for w in path.windows(2) {
+                            assert!(edges.contains(&(w[0], w[1])), "{:?}: no edge {} -> {}", model, w[0], w[1]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    let mut g = graph::CsrGraph::new(vec![1, 2, 3], vec![2, 3, 1]);
+    assert_eq!(traversal::hop_distance(&mut g, 2, 1), Some(2));
+    assert_eq!(traversal::hop_distance(&mut g, 9, 9), Some(0));
+}
+
+// == SHORTEST PATHS ==
+
+// Every table type as a weighted graph.
+fn all_weighted_graphs(src: &[i64], dst: &[i64], weights: graph::Weights) -> Vec<Box<dyn graph::WeightedGraph>> {
+    vec![
+        Box::new(graph::DeCrackedGraph::with_weights(src.to_vec(), dst.to_vec(), weights.clone())),
+        Box::new(graph::ReCoGraph::with_weights(src.to_vec(), dst.to_vec(), weights.clone())),
+        Box::new(graph::CoCoGraph::with_weights(src.to_vec(), dst.to_vec(), weights.clone())),
+        Box::new(graph::UnderswapRLEGraph::with_weights(src.to_vec(), dst.to_vec(), weights.clone())),
+        Box::new(graph::OverswapRLEGraph::with_weights(src.to_vec(), dst.to_vec(), weights)),
+    ]
+}
+
+// Distances from SOURCE by Bellman-Ford over the adjacency list.
+fn scanned_distances(src: &[i64], dst: &[i64], weight: &[f64], source: i64) -> HashMap<i64, f64> {
+    let mut distances = HashMap::new();
+    distances.insert(source, 0.0);
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for i in 0..src.len() {
+            if let Some(&d) = distances.get(&src[i]) {
+                if distances.get(&dst[i]).is_none_or(|&x| d + weight[i] < x) {
+                    distances.insert(dst[i], d + weight[i]);
+                    changed = true;
+                }
+            }
+        }
+    }
+    distances
+}
+
+#[test]
+fn weights_stay_aligned_through_cracking() {
+    for model in all_models() {
+        let (src, dst) = datagen::generate(&model, 2);
+        let weight: Vec<f64> = (0..src.len()).map(|i| (i * 37 % 101) as f64 / 4.0).collect();
+        for mut g in all_weighted_graphs(&src, &dst, graph::Weights::Float(weight.clone())) {
+            for v in 1..=model.nodes() {
+                let mut expected: Vec<(i64, f64)> = (0..src.len()).filter(|&i| src[i] == v).map(|i| (dst[i], weight[i])).collect();
+                let mut edges = g.out_edges(v);
+                expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                edges.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                assert_eq!(edges, expected, "{:?}: out edges of {}", model, v);
+            }
+        }
+    }
+    // Every edge has its own weight, some edges are repeated, and half of the vertices are cracked
+    // by an unweighted lookup first, so a weight read from the wrong tuple shows up.
+    let src = vec![3, 1, 2, 3, 1, 3, 2, 1, 3, 1, 2, 3];
+    let dst = vec![1, 2, 3, 2, 3, 1, 1, 2, 3, 4, 4, 2];
+    let weight: Vec<f64> = (0..src.len()).map(|i| i as f64 + 0.5).collect();
+    for mut g in all_weighted_graphs(&src, &dst, graph::Weights::Float(weight.clone())) {
+        g.out_neighbours(2);
+        for v in &[3, 1, 2, 3, 4, 1] {
+            let mut expected: Vec<(i64, f64)> = (0..src.len()).filter(|&i| src[i] == *v).map(|i| (dst[i], weight[i])).collect();
+            let mut edges = g.out_edges(*v);
+            expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            edges.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            assert_eq!(edges, expected, "out edges of {}", v);
+        }
+    }
+    let mut g = graph::DeCrackedGraph::new(vec![1, 1], vec![2, 3]);
+    assert_eq!(graph::WeightedGraph::out_edges(&mut g, 1).len(), 2);
+    assert!(graph::WeightedGraph::out_edges(&mut g, 1).iter().all(|e| e.1 == 1.0));
+}
+
+#[test]
+fn dijkstra_and_delta_stepping_find_shortest_distances() {
+    for model in all_models() {
+        let (src, dst) = datagen::generate(&model, 3);
+        let int_weight: Vec<i64> = (0..src.len() as i64).map(|i| i * 13 % 10).collect();
+        let weight: Vec<f64> = int_weight.iter().map(|&w| w as f64).collect();
+        let expected = scanned_distances(&src, &dst, &weight, 1);
+        for mut g in all_weighted_graphs(&src, &dst, graph::Weights::Int(int_weight.clone())) {
+            let by_dijkstra = sssp::dijkstra(&mut *g, 1);
+            assert_eq!(by_dijkstra.distances(), &expected, "{:?}", model);
+            for delta in &[0.5, 3.0, 100.0] {
+                assert_eq!(sssp::delta_stepping(&mut *g, 1, *delta).distances(), &expected, "{:?}: delta {}", model, delta);
+            }
+            for (&v, &d) in &expected {
+                let path = by_dijkstra.path_to(v).unwrap();
+                let length: f64 = path.windows(2)
+                    .map(|p| (0..src.len()).filter(|&i| src[i] == p[0] && dst[i] == p[1]).map(|i| weight[i]).fold(f64::INFINITY, f64::min))
+                    .sum();
+                assert_eq!(length, d, "{:?}: path to {}", model, v);
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "dijkstra: Edge 1 -> 2 has weight -1")]
+fn dijkstra_rejects_negative_weights() {
+    let mut g = graph::CoCoGraph::with_weights(vec![1], vec![2], graph::Weights::Float(vec![-1.0]));
+    sssp::dijkstra(&mut g, 1);
+}
+
+#[test]
+fn delta_stepping_handles_edges_far_longer_than_delta() {
+    let mut g = graph::CoCoGraph::with_weights(vec![1, 2, 1], vec![2, 3, 3], graph::Weights::Float(vec![1e12, 1.0, 2e12]));
+    let paths = sssp::delta_stepping(&mut g, 1, 0.001);
+    assert_eq!(paths.distances()[&3], 1e12 + 1.0);
+}
+
+#[test]
+#[should_panic(expected = "delta_stepping: Delta NaN is not positive")]
+fn delta_stepping_rejects_nan_delta() {
+    let mut g = graph::CoCoGraph::with_weights(vec![1], vec![2], graph::Weights::Float(vec![1.0]));
+    sssp::delta_stepping(&mut g, 1, f64::NAN);
+}
+
+#[test]
+#[should_panic(expected = "delta_stepping: Delta 0 is not positive")]
+fn delta_stepping_rejects_zero_delta() {
+    let mut g = graph::CoCoGraph::with_weights(vec![1], vec![2], graph::Weights::Float(vec![1.0]));
+    sssp::delta_stepping(&mut g, 1, 0.0);
+}
+
+// == COMPONENTS ==
+
+#[test]
+fn components_match_reachability() {
+    for model in all_models() {
+        let (src, dst) = datagen::generate(&model, 12);
+        // Drop some edges so that there are several components, and keep only one direction of
+        // others so that strong and weak components differ.
+        let kept: Vec<usize> = (0..src.len()).filter(|&i| i % 3 != 0 && (i % 5 != 0 || src[i] < dst[i])).collect();
+        let src: Vec<i64> = kept.iter().map(|&i| src[i]).collect();
+        let dst: Vec<i64> = kept.iter().map(|&i| dst[i]).collect();
+        let both_src: Vec<i64> = src.iter().chain(dst.iter()).cloned().collect();
+        let both_dst: Vec<i64> = dst.iter().chain(src.iter()).cloned().collect();
+        for mut g in all_graphs(&src, &dst) {
+            let weak = components::weakly_connected_components(&mut *g);
+            let strong = components::strongly_connected_components(&mut *g);
+            let vertices = g.vertices().to_vec();
+            assert_eq!(weak.sizes().iter().sum::<usize>(), vertices.len());
+            assert_eq!(strong.sizes().iter().sum::<usize>(), vertices.len());
+            for &v in vertices.iter().step_by(3) {
+                let undirected = scanned_depths(&both_src, &both_dst, v);
+                let forwards = scanned_depths(&src, &dst, v);
+                for &u in &vertices {
+                    assert_eq!(weak.component(v) == weak.component(u), undirected.contains_key(&u), "{:?}: {} and {}", model, v, u);
+                    let mutual = forwards.contains_key(&u) && scanned_depths(&src, &dst, u).contains_key(&v);
+                    assert_eq!(strong.component(v) == strong.component(u), mutual, "{:?}: {} and {}", model, v, u);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn generated_trees_have_one_component() {
+    for seed in 0..5 {
+        let (src, dst) = datagen::randomly_connected_tree(200, seed);
+        let mut g = graph::DeCrackedGraph::new(src, dst);
+        assert_eq!(components::weakly_connected_components(&mut g).count(), 1);
+        assert_eq!(components::strongly_connected_components(&mut g).count(), 1);
+    }
+    // 1 -> 2 -> 3 -> 1 and 3 -> 4, with 5 -> 6 apart.
+    let mut g = graph::CsrGraph::new(vec![1, 2, 3, 3, 5], vec![2, 3, 1, 4, 6]);
+    let weak = components::weakly_connected_components(&mut g);
+    assert_eq!(weak.sizes(), &[4, 2][..]);
+    assert_eq!(weak.members(1), vec![5, 6]);
+    let strong = components::strongly_connected_components(&mut g);
+    assert_eq!(strong.sizes(), &[3, 1, 1, 1][..]);
+    assert_eq!(strong.largest(), 3);
+    assert_eq!(strong.size_distribution(), vec![(1, 3), (3, 1)].into_iter().collect());
+    assert_eq!(strong.component(7), None);
+}
+
+// == CENTRALITY ==
+
+// The depth of, and number of shortest paths to, each vertex reached from SOURCE.
+fn scanned_path_counts(src: &[i64], dst: &[i64], source: i64) -> HashMap<i64, (usize, f64)> {
+    let depths = scanned_depths(src, dst, source);
+    let mut by_depth: Vec<(usize, i64)> = depths.iter().map(|(&v, &d)| (d, v)).collect();
+    by_depth.sort();
+    let mut counts: HashMap<i64, (usize, f64)> = HashMap::new();
+    for (d, v) in by_depth {
+        let paths = if d == 0 { 1.0 } else {
+            (0..src.len()).filter(|&i| dst[i] == v && depths.get(&src[i]) == Some(&(d - 1))).map(|i| counts[&src[i]].1).sum()
+        };
+        counts.insert(v, (d, paths));
+    }
+    counts
+}
+
+#[test]
+fn brandes_betweenness_matches_counting_shortest_paths() {
+    for model in all_models() {
+        let (src, dst) = datagen::generate(&model, 1);
+        let vertices = sorted(src.iter().chain(dst.iter()).cloned().collect::<HashSet<i64>>().into_iter().collect());
+        let counts: HashMap<i64, HashMap<i64, (usize, f64)>> = vertices.iter().map(|&v| (v, scanned_path_counts(&src, &dst, v))).collect();
+        // The fraction of shortest s-t paths through v, summed over every pair.
+        let mut expected: HashMap<i64, f64> = vertices.iter().map(|&v| (v, 0.0)).collect();
+        for &s in &vertices {
+            for (&t, &(st_depth, st_paths)) in &counts[&s] {
+                for &v in vertices.iter().filter(|&&v| v != s && v != t) {
+                    if let (Some(&(sv_depth, sv_paths)), Some(&(vt_depth, vt_paths))) = (counts[&s].get(&v), counts[&v].get(&t)) {
+                        if sv_depth + vt_depth == st_depth {
+                            *expected.get_mut(&v).unwrap() += sv_paths * vt_paths / st_paths;
+                        }
+                    }
+                }
+            }
+        }
+        for mut g in all_graphs(&src, &dst) {
+            let centrality = centrality::betweenness(&mut *g);
+            assert_eq!(centrality.len(), vertices.len());
+            for &v in &vertices {
+                assert!((centrality[&v] - expected[&v]).abs() < 1e-9, "{:?}: {} has {} not {}", model, v, centrality[&v], expected[&v]);
+            }
+        }
+    }
+}
+
+#[test]
+fn sampled_betweenness_is_reproducible_and_exact_with_every_sample() {
+    let (src, dst) = datagen::generate(&datagen::Model::BarabasiAlbert { n: 60, m: 2 }, 4);
+    let mut g = graph::UnderswapRLEGraph::new(src, dst);
+    let exact = centrality::betweenness(&mut g);
+    let all = centrality::sampled_betweenness(&mut g, 1000, 3);
+    for (v, c) in &exact {
+        assert!((all[v] - c).abs() < 1e-9);
+    }
+    let sampled = centrality::sampled_betweenness(&mut g, 20, 3);
+    assert_eq!(sampled, centrality::sampled_betweenness(&mut g, 20, 3));
+    assert!(sampled != centrality::sampled_betweenness(&mut g, 20, 4));
+    assert!(centrality::sampled_betweenness(&mut g, 0, 3).values().all(|&c| c == 0.0));
+}
+
+// == TRIANGLES ==
+
+#[test]
+fn triangle_counts_match_a_naive_count() {
+    for model in all_models() {
+        let (src, dst) = datagen::generate(&model, 10);
+        // Undirected edges between distinct vertices, straight from the adjacency list.
+        let edges: HashSet<(i64, i64)> = (0..src.len()).filter(|&i| src[i] != dst[i]).flat_map(|i| vec![(src[i], dst[i]), (dst[i], src[i])]).collect();
+        let vertices = sorted(src.iter().chain(dst.iter()).cloned().collect::<HashSet<i64>>().into_iter().collect());
+        let mut total = 0;
+        let mut per_vertex: HashMap<i64, usize> = HashMap::new();
+        for &a in &vertices {
+            for &b in vertices.iter().filter(|&&b| b > a && edges.contains(&(a, b))) {
+                for &c in vertices.iter().filter(|&&c| c > b && edges.contains(&(a, c)) && edges.contains(&(b, c))) {
+                    total += 1;
+                    for x in &[a, b, c] {
+                        *per_vertex.entry(*x).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+        for mut g in all_graphs(&src, &dst) {
+            let triangles = triangles::count_triangles(&mut *g);
+            assert_eq!(triangles.total, total, "{:?}", model);
+            for &v in &vertices {
+                let t = per_vertex.get(&v).cloned().unwrap_or(0);
+                let d = edges.iter().filter(|e| e.0 == v).count();
+                assert_eq!(triangles.triangles(v), t, "{:?}: triangles of {}", model, v);
+                let c = if d < 2 { 0.0 } else { 2.0 * t as f64 / (d * (d - 1)) as f64 };
+                assert_eq!(triangles.clustering_coefficient(v), c, "{:?}: clustering of {}", model, v);
+            }
+        }
+    }
+}
+
+#[test]
+fn clustering_coefficients_of_a_small_graph() {
+    // A triangle 1, 2, 3 with a tail 3 - 4, given in mixed directions and with a repeat.
+    let mut g = graph::ReCoGraph::new(vec![1, 3, 3, 3, 2], vec![2, 2, 1, 4, 3]);
+    assert_eq!(triangles::sorted_neighbours(&mut g, 3), vec![1, 2, 4]);
+    let triangles = triangles::count_triangles(&mut g);
+    assert_eq!(triangles.total, 1);
+    assert_eq!(triangles.clustering_coefficient(1), 1.0);
+    assert_eq!(triangles.clustering_coefficient(3), 1.0 / 3.0);
+    assert_eq!(triangles.clustering_coefficient(4), 0.0);
+    assert_eq!(triangles.average_clustering(), (1.0 + 1.0 + 1.0 / 3.0) / 4.0);
+}
+
+// == PAGERANK ==
+
+// Personalised PageRank by power iteration over the adjacency list, for vertices 1 to n, with the
+// rank of dangling vertices given back through the teleport distribution.
+fn scanned_pagerank(n: usize, src: &[i64], dst: &[i64], teleport: &[f64], damping: f64, iterations: usize) -> Vec<f64> {
+    let mut out_degree = vec![0; n + 1];
+    for &s in src {
+        out_degree[s as usize] += 1;
+    }
+    let mut rank = teleport.to_vec();
+    for _ in 0..iterations {
+        let dangling: f64 = (1..=n).filter(|&v| out_degree[v] == 0).map(|v| rank[v]).sum();
+        let mut new_rank: Vec<f64> = (0..=n).map(|v| (1.0 - damping + damping * dangling) * teleport[v]).collect();
+        for i in 0..src.len() {
+            new_rank[dst[i] as usize] += damping * rank[src[i] as usize] / out_degree[src[i] as usize] as f64;
+        }
+        rank = new_rank;
+    }
+    rank
+}
+
+// A config with the given damping, epsilon and iteration cap, and otherwise the defaults.
+fn pagerank_config(damping: f64, epsilon: f64, max_iterations: usize) -> ranking::PageRankConfig {
+    let mut config = ranking::PageRankConfig::new();
+    config.damping = damping;
+    config.epsilon = epsilon;
+    config.max_iterations = max_iterations;
+    config
+}
+
+#[test]
+fn pagerank_gives_back_the_rank_of_dangling_vertices() {
+    // Example from https://en.wikipedia.org/wiki/PageRank, where A (1) has no out-edges.
+    let src = vec![2, 3, 4, 4, 5, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 11];
+    let dst = vec![3, 2, 1, 2, 2, 4, 6, 2, 5, 2, 5, 2, 5, 2, 5, 5, 5];
+    let expected = vec![0.0328, 0.3844, 0.3429, 0.0391, 0.0809, 0.0391, 0.0162, 0.0162, 0.0162, 0.0162, 0.0162];
+    for mut g in all_graphs(&src, &dst) {
+        let ranks = ranking::run(&mut *g, &pagerank_config(0.85, 1e-9, 1000)).ranks;
+        assert!((ranks.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        for v in 1..=11 {
+            assert!((ranks[&v] - expected[v as usize - 1]).abs() < 0.0005, "{} has {} not {}", v, ranks[&v], expected[v as usize - 1]);
+        }
+    }
+}
+
+#[test]
+fn personalised_pagerank_matches_power_iteration() {
+    for model in all_models() {
+        let (src, dst) = datagen::generate(&model, 5);
+        // Drop edges so that there are dangling vertices.
+        let kept: Vec<usize> = (0..src.len()).filter(|&i| src[i] % 4 != 0).collect();
+        let src: Vec<i64> = kept.iter().map(|&i| src[i]).collect();
+        let dst: Vec<i64> = kept.iter().map(|&i| dst[i]).collect();
+        let n = model.nodes() as usize;
+        let mut config = pagerank_config(0.85, 0.0, 30);
+        config.teleport = (1..=n as i64).filter(|v| v % 5 == 0).map(|v| (v, 3.0)).chain(vec![(1, 1.0)]).collect();
+        // Vertices left without edges are ranked too.
+        config.vertices = (1..=n as i64).collect();
+        let total: f64 = config.teleport.values().sum();
+        let teleport: Vec<f64> = (0..=n as i64).map(|v| config.teleport.get(&v).map_or(0.0, |w| w / total)).collect();
+        let expected = scanned_pagerank(n, &src, &dst, &teleport, 0.85, 30);
+        for mut g in all_graphs(&src, &dst) {
+            let result = ranking::run(&mut *g, &config);
+            assert_eq!(result.ranks.len(), n);
+            assert_eq!(result.iterations, 30);
+            for (v, e) in expected.iter().enumerate().skip(1) {
+                assert!((result.ranks[&(v as i64)] - e).abs() < 1e-12, "{:?}: {} has {} not {}", model, v, result.ranks[&(v as i64)], e);
+            }
+        }
+    }
+}
+
+#[test]
+fn pagerank_stops_at_epsilon_or_the_iteration_cap() {
+    let (src, dst) = datagen::generate(&datagen::Model::Gnp { n: 50, p: 0.1 }, 2);
+    let mut g = graph::CsrGraph::new(src, dst);
+    // With no iterations, ranks are the teleport distribution.
+    let mut config = pagerank_config(0.85, 0.0, 0);
+    config.teleport = map!{7 => 2.0};
+    let result = ranking::run(&mut g, &config);
+    assert_eq!(result.ranks[&7], 1.0);
+    assert_eq!(result.ranks[&8], 0.0);
+    assert_eq!(result.iterations, 0);
+    assert!(!result.converged);
+    let coarse = ranking::run(&mut g, &pagerank_config(0.85, 0.1, 1000));
+    let fine = ranking::run(&mut g, &pagerank_config(0.85, 1e-12, 1000));
+    let capped = ranking::run(&mut g, &pagerank_config(0.85, 1e-12, 2));
+    assert!(coarse.converged && fine.converged && !capped.converged);
+    assert!(coarse.iterations < fine.iterations);
+    assert_eq!(capped.iterations, 2);
+    assert!(coarse.ranks != fine.ranks && capped.ranks != fine.ranks);
+    assert!(fine.ranks.iter().all(|(v, r)| (coarse.ranks[v] - r).abs() < 0.1));
+}
+
+#[test]
+fn pagerank_records_a_residual_per_iteration_by_the_configured_norm() {
+    let (src, dst) = datagen::generate(&datagen::Model::Gnp { n: 50, p: 0.1 }, 3);
+    let mut g = graph::PreclusteredGraph::new(src, dst);
+    let mut first = Vec::new();
+    for &norm in &[ranking::Norm::L1, ranking::Norm::L2, ranking::Norm::Max] {
+        let mut config = pagerank_config(0.85, 1e-4, 1000);
+        config.norm = norm;
+        let result = ranking::run(&mut g, &config);
+        assert_eq!(result.residuals.len(), result.iterations);
+        assert!(result.residuals[result.iterations - 1] < 1e-4);
+        assert!(result.residuals[..result.iterations - 1].iter().all(|&r| r >= 1e-4));
+        first.push(result.residuals[0]);
+    }
+    // The first iteration makes the same change whatever the norm, and L1 >= L2 >= max.
+    assert!(first[0] > first[1] && first[1] > first[2]);
+}
+
+#[test]
+fn pagerank_ranks_dense_and_sparse_ids_alike() {
+    let src = vec![1, 2, 3, 3];
+    let dst = vec![2, 3, 1, 4];
+    let sparse_src: Vec<i64> = src.iter().map(|v| v * 1000).collect();
+    let sparse_dst: Vec<i64> = dst.iter().map(|v| v * 1000).collect();
+    let config = pagerank_config(0.85, 1e-12, 1000);
+    let dense = ranking::build_and_run(graph::CoCoGraph::new, src, dst, &config);
+    let sparse = ranking::build_and_run(graph::CoCoGraph::new, sparse_src, sparse_dst, &config);
+    let ranks = dense.dense_ranks(5);
+    assert_eq!(ranks.len(), 6);
+    assert_eq!((ranks[0], ranks[5]), (0.0, 0.0));
+    assert_eq!(ranks[1..5].to_vec(), (1..5).map(|v| sparse.ranks[&(v * 1000)]).collect::<Vec<f64>>());
+}
+
+type PageRankKernel = fn(Vec<i64>, Vec<i64>, &ranking::PageRankConfig) -> ranking::PageRankResult;
+type PersonRankKernel = fn(Vec<i64>, Vec<i64>, Vec<i64>, &ranking::PageRankConfig) -> ranking::PageRankResult;
+
+#[test]
+fn pagerank_kernels_give_back_the_rank_of_sinks() {
+    // Half the vertices are sinks, and 100 and 200 have no edges at all.
+    let (src, dst) = datagen::generate(&datagen::Model::Gnp { n: 40, p: 0.1 }, 6);
+    let kept: Vec<usize> = (0..src.len()).filter(|&i| src[i] % 2 == 0).collect();
+    let src: Vec<i64> = kept.iter().map(|&i| src[i] * 7).collect();
+    let dst: Vec<i64> = kept.iter().map(|&i| dst[i] * 7).collect();
+    let config = pagerank_config(0.85, 1e-12, 1000);
+    let mut with_people = config.clone();
+    with_people.vertices = vec![100, 200];
+    let expected = ranking::run(&mut graph::ScanGraph::new(src.clone(), dst.clone()), &with_people).ranks;
+
+    let pageranks: Vec<(&str, PageRankKernel)> = vec![
+        ("Unoptimised", pagerank::unoptimised_pagerank),
+        ("Preclustered", pagerank::preclustered_pagerank),
+        ("Preclustered RLE", pagerank::preclustered_rle_pagerank),
+        ("Decracked", pagerank::decracked_pagerank),
+        ("Reco", pagerank::reco_pagerank),
+        ("Coco", pagerank::coco_pagerank),
+        ("Underswap RLE", pagerank::underswap_rle_pagerank),
+        ("Overswap RLE", pagerank::overswap_rle_pagerank),
+    ];
+    let personranks: Vec<(&str, PersonRankKernel)> = vec![
+        ("Unoptimised personrank", personrank::unoptimised_personrank),
+        ("Preclustered personrank", personrank::preclustered_personrank),
+        ("Decracked personrank", personrank::decracked_personrank),
+        ("Reco personrank", personrank::reco_personrank),
+        ("Coco personrank", personrank::coco_personrank),
+        ("Underswap personrank", personrank::underswap_personrank),
+        ("Overswap personrank", personrank::overswap_personrank),
+    ];
+    let results = pageranks.into_iter().map(|(name, pagerank)| (name, pagerank(src.clone(), dst.clone(), &with_people)))
+        .chain(personranks.into_iter().map(|(name, personrank)| (name, personrank(vec![100, 200], src.clone(), dst.clone(), &config))));
+    for (name, result) in results {
+        assert!(result.converged, "{}", name);
+        let total: f64 = result.ranks.values().sum();
+        assert!((total - 1.0).abs() < 1e-9, "{}: ranks sum to {}", name, total);
+        assert_eq!(result.ranks.len(), expected.len(), "{}", name);
+        for (v, r) in &expected {
+            assert!((result.ranks[v] - r).abs() < 1e-12, "{}: {} has {} not {}", name, v, result.ranks[v], r);
+        }
+    }
+}
+
+#[test]
+fn pagerank_looks_up_in_neighbours_once_per_vertex() {
+    let (src, dst) = datagen::generate(&datagen::Model::Gnp { n: 30, p: 0.2 }, 4);
+    let mut g = LookupCounter::new(graph::DeCrackedGraph::new(src, dst));
+    let result = ranking::run(&mut g, &pagerank_config(0.85, 1e-9, 1000));
+    assert!(result.iterations > 1);
+    assert_eq!(g.in_lookups.len(), result.ranks.len());
+    assert!(g.in_lookups.values().all(|&c| c == 1));
+}
+
+#[test]
+#[should_panic(expected = "teleport_distribution: Vertex 3 has teleport weight -1")]
+fn personalised_pagerank_rejects_negative_teleport_weights() {
+    let mut g = graph::DeCrackedGraph::new(vec![1, 2], vec![2, 3]);
+    let mut config = ranking::PageRankConfig::new();
+    config.teleport = map!{3 => -1.0};
+    ranking::run(&mut g, &config);
+}
+
+// Applies CHANGES to the adjacency list SRC, DST in order.
+fn apply_changes(src: &[i64], dst: &[i64], changes: &[ranking::EdgeChange]) -> (Vec<i64>, Vec<i64>) {
+    let mut edges: Vec<(i64, i64)> = src.iter().cloned().zip(dst.iter().cloned()).collect();
+    for change in changes {
+        match *change {
+            ranking::EdgeChange::Insert(u, v) => edges.push((u, v)),
+            ranking::EdgeChange::Delete(u, v) => {
+                let i = edges.iter().position(|&e| e == (u, v)).unwrap();
+                edges.remove(i);
+            },
+        }
+    }
+    edges.into_iter().unzip()
+}
+
+#[test]
+fn updated_pagerank_matches_pagerank_from_scratch() {
+    for model in all_models() {
+        let (src, dst) = datagen::generate(&model, 9);
+        let n = model.nodes();
+        // Insert edges to and from new vertices, and delete every out-edge of the first source.
+        let mut changes = vec![ranking::EdgeChange::Insert(1, n + 1), ranking::EdgeChange::Insert(n + 2, 2), ranking::EdgeChange::Insert(3, 3)];
+        changes.extend((0..src.len()).filter(|&i| src[i] == src[0]).map(|i| ranking::EdgeChange::Delete(src[i], dst[i])));
+        let (new_src, new_dst) = apply_changes(&src, &dst, &changes);
+        let vertices: Vec<i64> = (1..=n + 2).collect();
+        for personalised in &[false, true] {
+            let mut config = pagerank_config(0.85, 1e-13, 10000);
+            if *personalised {
+                config.personalise(2);
+            }
+            // Uniform teleports need the new vertices ranked already.
+            config.vertices = if *personalised { vertices[..n as usize].to_vec() } else { vertices.clone() };
+            let previous = ranking::run(&mut graph::CsrGraph::new(src.clone(), dst.clone()), &config).ranks;
+            config.vertices = vertices.clone();
+            let expected = ranking::run(&mut graph::CsrGraph::new(new_src.clone(), new_dst.clone()), &config).ranks;
+            config.push_threshold = 1e-12;
+            for mut g in all_graphs(&new_src, &new_dst) {
+                let result = ranking::update(&mut *g, &previous, &changes, &config);
+                assert!(result.converged);
+                assert_eq!(result.residuals.len(), result.iterations);
+                assert_eq!(result.ranks.len(), expected.len());
+                for (v, r) in &expected {
+                    assert!((result.ranks[v] - r).abs() < 1e-9, "{:?}: {} has {} not {}", model, v, result.ranks[v], r);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn updated_pagerank_looks_up_only_affected_vertices() {
+    // Two cycles, 1 to 5 and 6 to 10, with a chord added to the first.
+    let src = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+    let dst = vec![2, 3, 4, 5, 1, 7, 8, 9, 10, 6];
+    let changes = vec![ranking::EdgeChange::Insert(1, 3)];
+    let (new_src, new_dst) = apply_changes(&src, &dst, &changes);
+    let mut config = pagerank_config(0.85, 1e-12, 10000);
+    config.push_threshold = 1e-12;
+    let previous = ranking::run(&mut graph::ReCoGraph::new(src, dst), &config).ranks;
+    let mut g = LookupCounter::new(graph::ReCoGraph::new(new_src.clone(), new_dst.clone()));
+    let result = ranking::update(&mut g, &previous, &changes, &config);
+    assert_eq!(sorted(g.out_lookups.keys().cloned().collect()), vec![1, 2, 3, 4, 5]);
+    assert!(g.in_lookups.is_empty());
+    let expected = ranking::run(&mut graph::ReCoGraph::new(new_src, new_dst), &config).ranks;
+    for (v, r) in &expected {
+        assert!((result.ranks[v] - r).abs() < 1e-9);
+    }
+    // With no changes there's nothing to push.
+    let unchanged = ranking::update(&mut graph::ReCoGraph::new(vec![1], vec![2]), &previous, &[], &config);
+    assert_eq!((unchanged.ranks, unchanged.iterations, unchanged.converged), (previous, 0, true));
+}
+
+#[test]
+fn updated_pagerank_is_the_same_every_time() {
+    let (src, dst) = datagen::generate(&datagen::Model::Gnp { n: 60, p: 0.1 }, 10);
+    let changes: Vec<ranking::EdgeChange> = (1..=10).map(|v| ranking::EdgeChange::Insert(v, 61 - v)).collect();
+    let (new_src, new_dst) = apply_changes(&src, &dst, &changes);
+    let mut config = ranking::PageRankConfig::new();
+    config.vertices = (1..=60).collect();
+    let previous = ranking::run(&mut graph::CsrGraph::new(src, dst), &config).ranks;
+    let first = ranking::update(&mut graph::CsrGraph::new(new_src.clone(), new_dst.clone()), &previous, &changes, &config);
+    assert!(first.iterations > 1);
+    for _ in 0..5 {
+        let again = ranking::update(&mut graph::CsrGraph::new(new_src.clone(), new_dst.clone()), &previous, &changes, &config);
+        assert_eq!((&again.ranks, &again.residuals), (&first.ranks, &first.residuals));
+    }
+}
+
+#[test]
+fn updated_pagerank_pushes_until_the_push_threshold() {
+    let (src, dst) = datagen::generate(&datagen::Model::Gnp { n: 60, p: 0.1 }, 11);
+    let changes = vec![ranking::EdgeChange::Insert(1, 2), ranking::EdgeChange::Insert(3, 4)];
+    let (new_src, new_dst) = apply_changes(&src, &dst, &changes);
+    let mut config = ranking::PageRankConfig::new();
+    config.vertices = (1..=60).collect();
+    let previous = ranking::run(&mut graph::CsrGraph::new(src, dst), &config).ranks;
+    let mut rounds = Vec::new();
+    for &threshold in &[1e-3, 1e-6, 1e-9] {
+        config.push_threshold = threshold;
+        let result = ranking::update(&mut graph::CsrGraph::new(new_src.clone(), new_dst.clone()), &previous, &changes, &config);
+        assert!(result.converged);
+        rounds.push(result.iterations);
+    }
+    // Epsilon plays no part.
+    config.epsilon = 1.0;
+    let result = ranking::update(&mut graph::CsrGraph::new(new_src, new_dst), &previous, &changes, &config);
+    assert_eq!(result.iterations, rounds[2]);
+    assert!(rounds[0] < rounds[1] && rounds[1] < rounds[2]);
+}
+
+#[test]
+#[should_panic(expected = "update: Edge 2 -> 1 was inserted but isn't in the graph")]
+fn updated_pagerank_rejects_changes_missing_from_the_graph() {
+    let mut g = graph::CoCoGraph::new(vec![1, 2], vec![2, 3]);
+    let previous = ranking::run(&mut g, &ranking::PageRankConfig::new()).ranks;
+    ranking::update(&mut g, &previous, &[ranking::EdgeChange::Insert(2, 1)], &ranking::PageRankConfig::new());
+}
+
+#[test]
+#[should_panic(expected = "update: Vertex 4 has no previous rank, which uniform teleports need")]
+fn updated_pagerank_rejects_new_vertices_with_uniform_teleports() {
+    let previous = ranking::run(&mut graph::CoCoGraph::new(vec![1, 2], vec![2, 3]), &ranking::PageRankConfig::new()).ranks;
+    let mut g = graph::CoCoGraph::new(vec![1, 2, 3], vec![2, 3, 4]);
+    ranking::update(&mut g, &previous, &[ranking::EdgeChange::Insert(3, 4)], &ranking::PageRankConfig::new());
+}
+
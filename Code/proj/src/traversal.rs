@@ -0,0 +1,363 @@
+// Traversals over Graph views
+// The BFS functions in bfs.rs only return the vertices they reached. These keep the traversal
+// itself: each vertex's depth and the parent it was discovered from, which answer shortest
+// unweighted paths and hop-distance queries, such as LDBC's friends within 3 hops.
+// There's also a direction-optimising BFS, after Beamer et al. (Papers/DirectionOptimisedBFS).
+
+use bit_vec::BitVec;
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+use graph::Graph;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BfsTree {
+    pub start: i64,
+    // The vertices reached, in the order they were visited.
+    pub order: Vec<i64>,
+    depths: HashMap<i64, usize>,
+    parents: HashMap<i64, i64>,
+}
+
+impl BfsTree {
+    // Hops from the start to V, or None if V wasn't reached.
+    pub fn depth(&self, v: i64) -> Option<usize> {
+        self.depths.get(&v).cloned()
+    }
+
+    // The vertex V was discovered from. The start has no parent.
+    pub fn parent(&self, v: i64) -> Option<i64> {
+        self.parents.get(&v).cloned()
+    }
+
+    pub fn reached(&self, v: i64) -> bool {
+        self.depths.contains_key(&v)
+    }
+
+    // The reached vertices in ascending order, like the results of bfs.rs.
+    pub fn visited(&self) -> Vec<i64> {
+        let mut visited = self.order.clone();
+        visited.sort();
+        visited
+    }
+
+    // The vertices at most HOPS from the start, including it, in ascending order.
+    pub fn within(&self, hops: usize) -> Vec<i64> {
+        let mut within: Vec<i64> = self.order.iter().cloned().filter(|v| self.depths[v] <= hops).collect();
+        within.sort();
+        within
+    }
+
+    // A shortest path from the start to V, following parent pointers back from V.
+    pub fn path_to(&self, v: i64) -> Option<Vec<i64>> {
+        if !self.reached(v) {
+            return None;
+        }
+        let mut path = vec![v];
+        let mut current = v;
+        while let Some(parent) = self.parent(current) {
+            path.push(parent);
+            current = parent;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+// A BFS from START over outgoing edges which records depths and parents.
+pub fn bfs_tree<G: Graph + ?Sized>(graph: &mut G, start: i64) -> BfsTree {
+    bfs_tree_to_depth(graph, start, usize::MAX)
+}
+
+// A BFS from START which doesn't expand vertices at MAX_DEPTH, so only reaches vertices at most
+// MAX_DEPTH hops away. It does no lookups beyond that depth.
+pub fn bfs_tree_to_depth<G: Graph + ?Sized>(graph: &mut G, start: i64, max_depth: usize) -> BfsTree {
+    let mut tree = BfsTree { start, order: vec![start], depths: HashMap::new(), parents: HashMap::new() };
+    tree.depths.insert(start, 0);
+    let mut level_start = 0;
+    let mut depth = 0;
+    while depth < max_depth && level_start < tree.order.len() {
+        let level_end = tree.order.len();
+        for i in level_start..level_end {
+            let v = tree.order[i];
+            for u in graph.out_neighbours(v) {
+                if let Entry::Vacant(e) = tree.depths.entry(u) {
+                    e.insert(depth + 1);
+                    tree.parents.insert(u, v);
+                    tree.order.push(u);
+                }
+            }
+        }
+        level_start = level_end;
+        depth += 1;
+    }
+    tree
+}
+
+// The vertices at most HOPS from START, including it, in ascending order.
+pub fn within_hops<G: Graph + ?Sized>(graph: &mut G, start: i64, hops: usize) -> Vec<i64> {
+    bfs_tree_to_depth(graph, start, hops).visited()
+}
+
+// A shortest unweighted path from FROM to TO, or None if TO can't be reached. It's found by a
+// bidirectional BFS, which expands forwards from FROM over out-neighbours and backwards from TO
+// over in-neighbours, a level at a time on whichever side has the smaller frontier, and stops at
+// the level where the two sides meet.
+pub fn shortest_path<G: Graph + ?Sized>(graph: &mut G, from: i64, to: i64) -> Option<Vec<i64>> {
+    let mut forwards = Side::new(from);
+    let mut backwards = Side::new(to);
+    let mut meeting = if from == to { Some(from) } else { None };
+    while meeting.is_none() && !forwards.frontier.is_empty() && !backwards.frontier.is_empty() {
+        meeting = if forwards.frontier.len() <= backwards.frontier.len() {
+            forwards.expand(&backwards, |v| graph.out_neighbours(v))
+        } else {
+            backwards.expand(&forwards, |v| graph.in_neighbours(v))
+        };
+    }
+    meeting.map(|m| {
+        let mut path = forwards.path_to(m);
+        path.reverse();
+        path.pop();
+        path.extend(backwards.path_to(m));
+        path
+    })
+}
+
+// The number of hops on a shortest path from FROM to TO, or None if TO can't be reached.
+pub fn hop_distance<G: Graph + ?Sized>(graph: &mut G, from: i64, to: i64) -> Option<usize> {
+    shortest_path(graph, from, to).map(|p| p.len() - 1)
+}
+
+// One side of a bidirectional BFS.
+struct Side {
+    depths: HashMap<i64, usize>,
+    parents: HashMap<i64, i64>,
+    frontier: Vec<i64>,
+    depth: usize,
+}
+
+impl Side {
+    fn new(start: i64) -> Side {
+        let mut depths = HashMap::new();
+        depths.insert(start, 0);
+        Side { depths, parents: HashMap::new(), frontier: vec![start], depth: 0 }
+    }
+
+    // Expands the frontier by a level. Returns the vertex reached by the other side which gives
+    // the shortest path through this level, if any.
+    fn expand<F>(&mut self, other: &Side, mut neighbours: F) -> Option<i64> where F: FnMut(i64) -> Vec<i64> {
+        self.depth += 1;
+        let mut next = Vec::new();
+        let mut meeting: Option<(usize, i64)> = None;
+        for &v in &self.frontier {
+            for u in neighbours(v) {
+                if let Entry::Vacant(e) = self.depths.entry(u) {
+                    e.insert(self.depth);
+                    self.parents.insert(u, v);
+                    next.push(u);
+                    if let Some(&d) = other.depths.get(&u) {
+                        if meeting.is_none_or(|m| d < m.0) {
+                            meeting = Some((d, u));
+                        }
+                    }
+                }
+            }
+        }
+        self.frontier = next;
+        meeting.map(|m| m.1)
+    }
+
+    // The path from V back to this side's start.
+    fn path_to(&self, v: i64) -> Vec<i64> {
+        let mut path = vec![v];
+        let mut current = v;
+        while let Some(&parent) = self.parents.get(&current) {
+            path.push(parent);
+            current = parent;
+        }
+        path
+    }
+}
+
+// The heuristic's parameters, as tuned by Beamer et al. A BFS switches to bottom-up steps once the
+// frontier's edges outnumber the unexplored edges divided by ALPHA, and back to top-down steps
+// once the frontier shrinks below the vertices divided by BETA. An ALPHA of 0 never steps bottom-up.
+pub const ALPHA: usize = 14;
+pub const BETA: usize = 24;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Step {
+    // Looks up the out-neighbours of each vertex in the frontier.
+    TopDown,
+    // Looks up the in-neighbours of each unvisited vertex until one is in the frontier.
+    BottomUp,
+}
+
+// A direction-optimising BFS from START. Returns the visited vertices in ascending order, like the
+// results of bfs.rs.
+pub fn direction_optimising_bfs<G: Graph + ?Sized>(graph: &mut G, start: i64) -> Vec<i64> {
+    direction_optimising_bfs_steps(graph, start, ALPHA, BETA).0
+}
+
+// A direction-optimising BFS with the given heuristic parameters. Returns the visited vertices in
+// ascending order and the direction of each step. The frontiers and visited set are bitmaps over
+// the positions of vertices in graph.vertices().
+pub fn direction_optimising_bfs_steps<G: Graph + ?Sized>(graph: &mut G, start: i64, alpha: usize, beta: usize) -> (Vec<i64>, Vec<Step>) {
+    let vertices = graph.vertices().to_vec();
+    let n = vertices.len();
+    let start_position = match vertices.binary_search(&start) {
+        Ok(p) => p,
+        Err(_) => return (vec![start], vec![]),
+    };
+
+    let mut visited = BitVec::from_elem(n, false);
+    let mut frontier = BitVec::from_elem(n, false);
+    visited.set(start_position, true);
+    frontier.set(start_position, true);
+    let mut frontier_size: usize = 1;
+    // The out-neighbours of each vertex in the frontier, looked up once when it's discovered to
+    // count the frontier's edges, and kept for a top-down step.
+    let mut frontier_neighbours = vec![graph.out_neighbours(start)];
+    // Edges out of the frontier, and out of the vertices not yet visited.
+    let mut frontier_edges = frontier_neighbours[0].len();
+    let mut unexplored_edges = graph.edge_count() - frontier_edges;
+
+    let mut steps = Vec::new();
+    let mut step = Step::TopDown;
+    while frontier_size > 0 {
+        step = match step {
+            Step::TopDown if frontier_edges.saturating_mul(alpha) > unexplored_edges => Step::BottomUp,
+            Step::BottomUp if frontier_size.saturating_mul(beta) < n => Step::TopDown,
+            step => step,
+        };
+        steps.push(step);
+
+        let mut next = BitVec::from_elem(n, false);
+        let mut discovered = Vec::new();
+        match step {
+            Step::TopDown => {
+                for neighbours in &frontier_neighbours {
+                    for &u in neighbours {
+                        let q = vertices.binary_search(&u).unwrap();
+                        if !visited[q] {
+                            visited.set(q, true);
+                            next.set(q, true);
+                            discovered.push(u);
+                        }
+                    }
+                }
+            }
+            Step::BottomUp => {
+                for q in (0..n).filter(|&q| !visited[q]) {
+                    let parent = graph.in_neighbours(vertices[q]).into_iter()
+                        .find(|u| frontier[vertices.binary_search(u).unwrap()]);
+                    if parent.is_some() {
+                        next.set(q, true);
+                        discovered.push(vertices[q]);
+                    }
+                }
+                for (q, found) in next.iter().enumerate() {
+                    if found {
+                        visited.set(q, true);
+                    }
+                }
+            }
+        }
+
+        frontier = next;
+        frontier_size = discovered.len();
+        frontier_neighbours = discovered.into_iter().map(|u| graph.out_neighbours(u)).collect();
+        frontier_edges = frontier_neighbours.iter().map(Vec::len).sum();
+        unexplored_edges -= frontier_edges;
+    }
+
+    let reached = (0..n).filter(|&p| visited[p]).map(|p| vertices[p]).collect();
+    (reached, steps)
+}
+
+// Multi-source BFS, after Then et al.'s MS-BFS. Up to 64 BFSs run together, each owning one bit of
+// a word per vertex, so that a vertex's neighbours are looked up once per level for all of them.
+// More sources run in batches of 64.
+const BATCH: usize = 64;
+
+// Runs a BFS from each of SOURCES, calling VISIT(i, v, depth) when the BFS from sources[i] reaches
+// v, including at depth 0 for the source itself.
+pub fn multi_source_bfs<G, F>(graph: &mut G, sources: &[i64], mut visit: F) where G: Graph + ?Sized, F: FnMut(usize, i64, usize) {
+    let vertices = graph.vertices().to_vec();
+    for (b, batch) in sources.chunks(BATCH).enumerate() {
+        let mut seen = vec![0u64; vertices.len()];
+        let mut frontier = vec![0u64; vertices.len()];
+        for (i, &s) in batch.iter().enumerate() {
+            visit(b * BATCH + i, s, 0);
+            if let Ok(p) = vertices.binary_search(&s) {
+                seen[p] |= 1 << i;
+                frontier[p] |= 1 << i;
+            }
+        }
+
+        let mut depth = 0;
+        while frontier.iter().any(|&f| f != 0) {
+            depth += 1;
+            let mut next = vec![0u64; vertices.len()];
+            for p in 0..vertices.len() {
+                if frontier[p] == 0 {
+                    continue;
+                }
+                for u in graph.out_neighbours(vertices[p]) {
+                    let q = vertices.binary_search(&u).unwrap();
+                    let discovered = frontier[p] & !seen[q];
+                    if discovered != 0 {
+                        next[q] |= discovered;
+                        seen[q] |= discovered;
+                    }
+                }
+            }
+            for (q, &bits) in next.iter().enumerate() {
+                let mut bits = bits;
+                while bits != 0 {
+                    let i = bits.trailing_zeros() as usize;
+                    visit(b * BATCH + i, vertices[q], depth);
+                    bits &= bits - 1;
+                }
+            }
+            frontier = next;
+        }
+    }
+}
+
+// The hop distance from each of SOURCES to every vertex it reaches.
+pub fn multi_source_depths<G: Graph + ?Sized>(graph: &mut G, sources: &[i64]) -> Vec<HashMap<i64, usize>> {
+    let mut depths = vec![HashMap::new(); sources.len()];
+    multi_source_bfs(graph, sources, |i, v, depth| { depths[i].insert(v, depth); });
+    depths
+}
+
+// The closeness centrality of each of SOURCES: the number of other vertices it reaches divided by
+// the sum of their distances from it, or 0 if it reaches none.
+pub fn closeness_centrality<G: Graph + ?Sized>(graph: &mut G, sources: &[i64]) -> HashMap<i64, f64> {
+    let mut reached = vec![0usize; sources.len()];
+    let mut distance = vec![0usize; sources.len()];
+    multi_source_bfs(graph, sources, |i, _, depth| {
+        if depth > 0 {
+            reached[i] += 1;
+            distance[i] += depth;
+        }
+    });
+    (0..sources.len())
+        .map(|i| (sources[i], if distance[i] == 0 { 0.0 } else { reached[i] as f64 / distance[i] as f64 }))
+        .collect()
+}
+
+// The number of (source, vertex) pairs at each hop distance, from BFSs from each of SOURCES. Pass
+// every vertex as a source for all-pairs statistics.
+pub fn hop_distribution<G: Graph + ?Sized>(graph: &mut G, sources: &[i64]) -> Vec<usize> {
+    let mut distribution = Vec::new();
+    multi_source_bfs(graph, sources, |_, _, depth| {
+        if depth >= distribution.len() {
+            distribution.resize(depth + 1, 0);
+        }
+        distribution[depth] += 1;
+    });
+    distribution
+}
@@ -0,0 +1,88 @@
+// Triangles and clustering coefficients
+// Counts the triangles of a graph taken as undirected, and each vertex's local clustering
+// coefficient: the fraction of pairs of its neighbours which are neighbours themselves.
+// A vertex's neighbours come from the pieces for it in the src and dst cracked tables, which a
+// lookup cracks out on demand. Each triangle is found by merging the sorted neighbours of the two
+// ends of an edge, looked up for that edge, so there's no copy of the whole graph to hold; once a
+// vertex's pieces are cracked, looking it up again is cheap.
+
+use std::collections::HashMap;
+
+use graph::Graph;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Triangles {
+    pub total: usize,
+    // The triangles each vertex is in, and its undirected degree.
+    per_vertex: HashMap<i64, (usize, usize)>,
+}
+
+impl Triangles {
+    pub fn triangles(&self, v: i64) -> usize {
+        self.per_vertex.get(&v).map_or(0, |t| t.0)
+    }
+
+    // 0 for vertices with fewer than two neighbours.
+    pub fn clustering_coefficient(&self, v: i64) -> f64 {
+        match self.per_vertex.get(&v) {
+            Some(&(t, d)) if d >= 2 => 2.0 * t as f64 / (d * (d - 1)) as f64,
+            _ => 0.0,
+        }
+    }
+
+    // The mean of the vertices' clustering coefficients.
+    pub fn average_clustering(&self) -> f64 {
+        if self.per_vertex.is_empty() {
+            return 0.0;
+        }
+        // Summed in order of vertex, so that the result doesn't depend on the HashMap's order.
+        let mut vertices: Vec<i64> = self.per_vertex.keys().cloned().collect();
+        vertices.sort();
+        vertices.iter().map(|&v| self.clustering_coefficient(v)).sum::<f64>() / vertices.len() as f64
+    }
+}
+
+// The neighbours of V over edges in either direction, without V itself, in ascending order.
+pub fn sorted_neighbours<G: Graph + ?Sized>(graph: &mut G, v: i64) -> Vec<i64> {
+    let mut neighbours = graph.out_neighbours(v);
+    neighbours.extend(graph.in_neighbours(v));
+    neighbours.retain(|&u| u != v);
+    neighbours.sort();
+    neighbours.dedup();
+    neighbours
+}
+
+pub fn count_triangles<G: Graph + ?Sized>(graph: &mut G) -> Triangles {
+    let vertices = graph.vertices().to_vec();
+    let mut triangles = Triangles {
+        total: 0,
+        per_vertex: HashMap::new(),
+    };
+    // Each triangle v < u < w is found once, from its edge v - u.
+    for &v in &vertices {
+        let v_neighbours = sorted_neighbours(graph, v);
+        triangles.per_vertex.entry(v).or_insert((0, 0)).1 = v_neighbours.len();
+        for &u in v_neighbours.iter().filter(|&&u| u > v) {
+            let u_neighbours = sorted_neighbours(graph, u);
+            let (mut i, mut j) = (0, 0);
+            while i < v_neighbours.len() && j < u_neighbours.len() {
+                if v_neighbours[i] < u_neighbours[j] {
+                    i += 1;
+                } else if v_neighbours[i] > u_neighbours[j] {
+                    j += 1;
+                } else {
+                    let w = v_neighbours[i];
+                    if w > u {
+                        triangles.total += 1;
+                        for &x in &[v, u, w] {
+                            triangles.per_vertex.entry(x).or_insert((0, 0)).0 += 1;
+                        }
+                    }
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+    }
+    triangles
+}
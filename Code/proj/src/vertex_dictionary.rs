@@ -0,0 +1,91 @@
+// Vertex dictionary
+// The traversals index bitmaps by id - 1 and PageRank indexes rank vectors by id, so both assume
+// vertices are numbered 1 to n. Loaded graphs, like LDBC's, have sparse 64 bit IDs which may be
+// zero or negative. A dictionary maps these external IDs to dense internal IDs 1 to n, in the
+// order they were first seen, and maps results back.
+
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VertexDictionary {
+    internal: HashMap<i64, i64>,
+    // The external ID of internal ID i is at i - 1.
+    external: Vec<i64>,
+}
+
+impl VertexDictionary {
+    pub fn new() -> VertexDictionary {
+        VertexDictionary::default()
+    }
+
+    // A dictionary over the given vertices, so that vertices without edges get IDs too.
+    pub fn from_vertices(vertices: &[i64]) -> VertexDictionary {
+        let mut dictionary = VertexDictionary::new();
+        for &v in vertices {
+            dictionary.intern(v);
+        }
+        dictionary
+    }
+
+    // Returns the internal ID of V, giving it the next one if it has none yet.
+    pub fn intern(&mut self, v: i64) -> i64 {
+        if let Some(&i) = self.internal.get(&v) {
+            return i;
+        }
+        self.external.push(v);
+        let i = self.external.len() as i64;
+        self.internal.insert(v, i);
+        i
+    }
+
+    pub fn internal(&self, v: i64) -> Option<i64> {
+        self.internal.get(&v).cloned()
+    }
+
+    pub fn external(&self, i: i64) -> Option<i64> {
+        if i < 1 {
+            return None;
+        }
+        self.external.get(i as usize - 1).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.external.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.external.is_empty()
+    }
+
+    // Translates an adjacency list to internal IDs, interning any new vertices.
+    pub fn encode_edges(&mut self, src: &[i64], dst: &[i64]) -> (Vec<i64>, Vec<i64>) {
+        if src.len() != dst.len() {
+            panic!("encode_edges: {} sources but {} destinations", src.len(), dst.len());
+        }
+        let mut dense_src = Vec::with_capacity(src.len());
+        let mut dense_dst = Vec::with_capacity(dst.len());
+        for i in 0..src.len() {
+            dense_src.push(self.intern(src[i]));
+            dense_dst.push(self.intern(dst[i]));
+        }
+        (dense_src, dense_dst)
+    }
+
+    // Translates internal IDs, such as the vertices a traversal visited, back to external IDs.
+    pub fn decode(&self, internal: &[i64]) -> Vec<i64> {
+        internal.iter().map(|&i| self.external(i).unwrap_or_else(|| panic!("decode: No vertex has internal ID {}", i))).collect()
+    }
+
+    // Keys a vector of values indexed by internal ID, such as PageRank's ranks, by external ID.
+    // Position 0 is not a vertex and is ignored.
+    pub fn decode_values<T: Clone>(&self, values: &[T]) -> HashMap<i64, T> {
+        (1..values.len().min(self.len() + 1)).map(|i| (self.external[i - 1], values[i].clone())).collect()
+    }
+}
+
+// Renumbers the vertices of an adjacency list densely from 1.
+pub fn densify(src: &[i64], dst: &[i64]) -> (VertexDictionary, Vec<i64>, Vec<i64>) {
+    let mut dictionary = VertexDictionary::new();
+    let (src, dst) = dictionary.encode_edges(src, dst);
+    (dictionary, src, dst)
+}
@@ -0,0 +1,163 @@
+// Query workloads
+// Generates sequences of range queries in the access patterns used throughout the adaptive
+// indexing literature, e.g. Halim et al., "Stochastic Database Cracking".
+
+use time::PreciseTime;
+use time::Duration;
+use rand::Rng;
+
+use datagen;
+
+use decomposed_cracking;
+use recognitive_compression;
+use compactive_compression;
+use underswap_rle_compression;
+use overswap_rle_compression;
+
+// An inclusive range of values to select.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Query {
+    pub low: i64,
+    pub high: i64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pattern {
+    // Queries are placed uniformly at random across the domain.
+    Random,
+    // Each query starts where the previous one ended, starting again at low once it reaches the end
+    // of the domain.
+    Sequential,
+    // Query positions follow a zipfian distribution with the given exponent, so that the low end
+    // of the domain is hot.
+    Skewed(f64),
+    // Queries stride across the domain, returning to the start after the given number of queries.
+    Periodic(usize),
+    // Queries alternate between the two ends of the domain and converge on the middle.
+    ZoomIn,
+    // Queries start in the middle of the domain and alternately move outwards to either end.
+    ZoomOut,
+}
+
+// Generates N queries over the values LOW to HIGH inclusive, where each query selects the given
+// fraction of the domain. The same seed always generates the same queries.
+pub fn generate(pattern: Pattern, low: i64, high: i64, selectivity: f64, n: usize, seed: usize) -> Vec<Query> {
+    if high < low {
+        panic!("generate: Empty domain {}..{}", low, high);
+    }
+    if !(selectivity > 0.0 && selectivity <= 1.0) {
+        panic!("generate: Selectivity {} is not in (0, 1]", selectivity);
+    }
+
+    // Widened, as the domain of a wide range of i64s doesn't fit in one.
+    let domain = high as i128 - low as i128 + 1;
+    if domain > usize::MAX as i128 {
+        panic!("generate: Domain {}..{} has {} values, more than {}", low, high, domain, usize::MAX);
+    }
+    let domain = domain as usize;
+    let width = ((selectivity * domain as f64).round() as usize).max(1);
+    // Queries start at one of these offsets from LOW.
+    let positions = domain - width + 1;
+    let mut rng = datagen::seeded_rng(seed);
+
+    let offsets: Vec<usize> = match pattern {
+        Pattern::Random => (0..n).map(|_| rng.gen_range(0, positions)).collect(),
+        Pattern::Sequential => {
+            let steps = 1 + (positions - 1) / width;
+            (0..n).map(|i| (i % steps) * width).collect()
+        },
+        Pattern::Skewed(s) => {
+            let cdf = zipf_cdf(positions, s);
+            (0..n).map(|_| {
+                let u = rng.gen::<f64>() * cdf[positions - 1];
+                match cdf.binary_search_by(|c| c.partial_cmp(&u).unwrap()) {
+                    Ok(k) | Err(k) => k.min(positions - 1),
+                }
+            }).collect()
+        },
+        Pattern::Periodic(period) => {
+            if period == 0 {
+                panic!("generate: Periodic workloads need a period of at least one query");
+            }
+            let stride = if period > 1 { (positions - 1) / (period - 1) } else { 0 };
+            (0..n).map(|i| (i % period) * stride).collect()
+        },
+        Pattern::ZoomIn => {
+            // Steps from either end until the two sides meet in the middle, then starts again.
+            let steps = 1 + (positions - 1) / width / 2;
+            (0..n).map(|i| {
+                let k = (i / 2) % steps;
+                if i % 2 == 0 { k * width } else { positions - 1 - k * width }
+            }).collect()
+        },
+        Pattern::ZoomOut => {
+            // Steps from the middle until either side reaches an end, then starts again.
+            let middle = (positions - 1) / 2;
+            let steps = 1 + middle / width;
+            (0..n).map(|i| {
+                let k = (i / 2) % steps;
+                if i % 2 == 0 { middle - k * width } else { (middle + (k + 1) * width).min(positions - 1) }
+            }).collect()
+        },
+    };
+
+    offsets.iter().map(|&o| Query { low: (low as i128 + o as i128) as i64, high: (low as i128 + (o + width) as i128 - 1) as i64 }).collect()
+}
+
+// The cumulative weights of ranks 1 to N under a zipfian distribution with exponent S.
+fn zipf_cdf(n: usize, s: f64) -> Vec<f64> {
+    let mut cdf = Vec::with_capacity(n);
+    let mut sum = 0.0;
+    for k in 1..(n + 1) {
+        sum += (k as f64).powf(-s);
+        cdf.push(sum);
+    }
+    cdf
+}
+
+// Runs each query as a point select of every value in its range, returning the time each query
+// took. Works with any table through its cracker_select_specific.
+pub fn run<F>(queries: &[Query], mut select: F) -> Vec<Duration> where F: FnMut(i64) -> Vec<i64> {
+    let mut times = Vec::with_capacity(queries.len());
+    for q in queries {
+        let start = PreciseTime::now();
+        for x in q.low..(q.high + 1) {
+            select(x);
+        }
+        times.push(start.to(PreciseTime::now()));
+    }
+    times
+}
+
+// Prints to stdout a csv of the cumulative time taken by each table type to answer the workload's
+// queries on the src column of the given adjacency list, one line per query.
+pub fn benchmark_csv(pattern: Pattern, src: Vec<i64>, dst: Vec<i64>, selectivity: f64, n: usize, seed: usize) {
+    let low = *src.iter().min().expect("benchmark_csv: No edges");
+    let high = *src.iter().max().unwrap();
+    let queries = generate(pattern, low, high, selectivity, n, seed);
+
+    let mut decracked = decomposed_cracking::from_adjacency_vectors(src.clone(), dst.clone(), "src");
+    let mut reco = recognitive_compression::from_adjacency_vectors(src.clone(), dst.clone(), "src");
+    let mut coco = compactive_compression::from_adjacency_vectors(src.clone(), dst.clone(), "src");
+    let mut underswap = underswap_rle_compression::from_adjacency_vectors(src.clone(), dst.clone(), "src");
+    let mut overswap = overswap_rle_compression::from_adjacency_vectors(src, dst, "src");
+
+    let times = [
+        run(&queries, |x| decracked.cracker_select_specific(x, "dst")),
+        run(&queries, |x| reco.cracker_select_specific(x, "dst")),
+        run(&queries, |x| coco.cracker_select_specific(x, "dst")),
+        run(&queries, |x| underswap.cracker_select_specific(x, "dst")),
+        run(&queries, |x| overswap.cracker_select_specific(x, "dst")),
+    ];
+
+    println!("query,low,high,decomposed,recognitive,compactive,underswapRLE,overswapRLE");
+    let mut cumulative = [Duration::zero(); 5];
+    for i in 0..queries.len() {
+        print!("{},{},{}", i, queries[i].low, queries[i].high);
+        for j in 0..times.len() {
+            cumulative[j] = cumulative[j] + times[j][i];
+            print!(",{}", cumulative[j]);
+        }
+        println!();
+    }
+}